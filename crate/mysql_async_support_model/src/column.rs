@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{ColumnFlags, ColumnType};
+
+/// Descriptor of a column in a result set.
+///
+/// This is captured from the column definitions the server sends before the
+/// rows, so it is available even when the result set has no rows.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Column {
+    /// Name of the column in the result set, which may be an alias.
+    pub name: String,
+    /// Name of the column in its table, empty if the column is computed.
+    pub org_name: String,
+    /// Name of the table in the result set, which may be an alias.
+    pub table: String,
+    /// Name of the table the column belongs to, empty if the column is
+    /// computed.
+    pub org_table: String,
+    /// Schema the table belongs to, empty if the column is computed.
+    pub schema: String,
+    /// MySQL type of the column.
+    pub column_type: ColumnType,
+    /// Attributes of the column, such as whether it is nullable.
+    pub flags: ColumnFlags,
+    /// Character set and collation ID of the column's values.
+    ///
+    /// `63` is the `binary` character set.
+    pub character_set: u16,
+    /// Maximum display length of the column's values.
+    pub column_length: u32,
+    /// Number of decimal places for numeric and temporal columns.
+    pub decimals: u8,
+}
+
+impl Column {
+    /// Character set ID of binary strings, such as `BLOB` and `VARBINARY`.
+    pub const CHARACTER_SET_BINARY: u16 = 63;
+}
+
+impl From<&mysql_async::Column> for Column {
+    fn from(column: &mysql_async::Column) -> Self {
+        Self {
+            name: column.name_str().into_owned(),
+            org_name: column.org_name_str().into_owned(),
+            table: column.table_str().into_owned(),
+            org_table: column.org_table_str().into_owned(),
+            schema: column.schema_str().into_owned(),
+            column_type: ColumnType::from(column.column_type()),
+            flags: ColumnFlags::from(column.flags()),
+            character_set: column.character_set(),
+            column_length: column.column_length(),
+            decimals: column.decimals(),
+        }
+    }
+}
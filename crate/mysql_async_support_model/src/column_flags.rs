@@ -0,0 +1,69 @@
+use mysql_async::consts::ColumnFlags as MySqlColumnFlags;
+use serde::{Deserialize, Serialize};
+
+/// Attributes of a column in a result set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ColumnFlags {
+    /// Whether the column may contain `NULL`.
+    pub nullable: bool,
+    /// Whether the column is a numeric type declared `UNSIGNED`.
+    pub unsigned: bool,
+    /// Whether the column is a numeric type declared `ZEROFILL`.
+    pub zerofill: bool,
+    /// Whether the column holds binary data, or uses a binary collation.
+    pub binary: bool,
+    /// Whether the column is part of the primary key.
+    pub primary_key: bool,
+    /// Whether the column is part of a unique key.
+    pub unique_key: bool,
+    /// Whether the column is part of a non-unique key.
+    pub multiple_key: bool,
+    /// Whether the column is declared `AUTO_INCREMENT`.
+    pub auto_increment: bool,
+}
+
+impl From<MySqlColumnFlags> for ColumnFlags {
+    fn from(flags: MySqlColumnFlags) -> Self {
+        Self {
+            nullable: !flags.contains(MySqlColumnFlags::NOT_NULL_FLAG),
+            unsigned: flags.contains(MySqlColumnFlags::UNSIGNED_FLAG),
+            zerofill: flags.contains(MySqlColumnFlags::ZEROFILL_FLAG),
+            binary: flags.contains(MySqlColumnFlags::BINARY_FLAG),
+            primary_key: flags.contains(MySqlColumnFlags::PRI_KEY_FLAG),
+            unique_key: flags.contains(MySqlColumnFlags::UNIQUE_KEY_FLAG),
+            multiple_key: flags.contains(MySqlColumnFlags::MULTIPLE_KEY_FLAG),
+            auto_increment: flags.contains(MySqlColumnFlags::AUTO_INCREMENT_FLAG),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_async::consts::ColumnFlags as MySqlColumnFlags;
+
+    use super::ColumnFlags;
+
+    #[test]
+    fn from_mysql_column_flags() {
+        let flags = MySqlColumnFlags::NOT_NULL_FLAG
+            | MySqlColumnFlags::UNSIGNED_FLAG
+            | MySqlColumnFlags::PRI_KEY_FLAG
+            | MySqlColumnFlags::AUTO_INCREMENT_FLAG;
+
+        assert_eq!(
+            ColumnFlags {
+                nullable: false,
+                unsigned: true,
+                primary_key: true,
+                auto_increment: true,
+                ..ColumnFlags::default()
+            },
+            ColumnFlags::from(flags)
+        );
+    }
+
+    #[test]
+    fn from_mysql_column_flags_empty_is_nullable() {
+        assert!(ColumnFlags::from(MySqlColumnFlags::empty()).nullable);
+    }
+}
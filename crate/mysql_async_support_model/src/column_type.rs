@@ -0,0 +1,109 @@
+use mysql_async::consts::ColumnType as MySqlColumnType;
+use serde::{Deserialize, Serialize};
+
+/// MySQL type of a column, as sent by the server.
+///
+/// See <https://dev.mysql.com/doc/dev/mysql-server/latest/field__types_8h.html>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum ColumnType {
+    /// `DECIMAL` from servers before MySQL 5.0.
+    Decimal,
+    /// `TINYINT`, and `BOOL`.
+    Tiny,
+    /// `SMALLINT`.
+    Short,
+    /// `INT`.
+    Long,
+    /// `FLOAT`.
+    Float,
+    /// `DOUBLE`.
+    Double,
+    /// `NULL` literal.
+    Null,
+    /// `TIMESTAMP`.
+    Timestamp,
+    /// `BIGINT`.
+    LongLong,
+    /// `MEDIUMINT`.
+    Int24,
+    /// `DATE`.
+    Date,
+    /// `TIME`.
+    Time,
+    /// `DATETIME`.
+    DateTime,
+    /// `YEAR`.
+    Year,
+    /// `DATE`, internal to the server.
+    NewDate,
+    /// `VARCHAR`, internal to the server.
+    VarChar,
+    /// `BIT`.
+    Bit,
+    /// `TIMESTAMP` with fractional seconds, internal to the server.
+    Timestamp2,
+    /// `DATETIME` with fractional seconds, internal to the server.
+    DateTime2,
+    /// `TIME` with fractional seconds, internal to the server.
+    Time2,
+    /// `JSON`.
+    Json,
+    /// `DECIMAL` and `NUMERIC`.
+    NewDecimal,
+    /// `ENUM`.
+    Enum,
+    /// `SET`.
+    Set,
+    /// `TINYBLOB` and `TINYTEXT`.
+    TinyBlob,
+    /// `MEDIUMBLOB` and `MEDIUMTEXT`.
+    MediumBlob,
+    /// `LONGBLOB` and `LONGTEXT`.
+    LongBlob,
+    /// `BLOB` and `TEXT`.
+    Blob,
+    /// `VARCHAR` and `VARBINARY`.
+    VarString,
+    /// `CHAR` and `BINARY`.
+    String,
+    /// Spatial types, such as `GEOMETRY` and `POINT`.
+    Geometry,
+}
+
+impl From<MySqlColumnType> for ColumnType {
+    fn from(column_type: MySqlColumnType) -> Self {
+        match column_type {
+            MySqlColumnType::MYSQL_TYPE_DECIMAL => Self::Decimal,
+            MySqlColumnType::MYSQL_TYPE_TINY => Self::Tiny,
+            MySqlColumnType::MYSQL_TYPE_SHORT => Self::Short,
+            MySqlColumnType::MYSQL_TYPE_LONG => Self::Long,
+            MySqlColumnType::MYSQL_TYPE_FLOAT => Self::Float,
+            MySqlColumnType::MYSQL_TYPE_DOUBLE => Self::Double,
+            MySqlColumnType::MYSQL_TYPE_NULL => Self::Null,
+            MySqlColumnType::MYSQL_TYPE_TIMESTAMP => Self::Timestamp,
+            MySqlColumnType::MYSQL_TYPE_LONGLONG => Self::LongLong,
+            MySqlColumnType::MYSQL_TYPE_INT24 => Self::Int24,
+            MySqlColumnType::MYSQL_TYPE_DATE => Self::Date,
+            MySqlColumnType::MYSQL_TYPE_TIME => Self::Time,
+            MySqlColumnType::MYSQL_TYPE_DATETIME => Self::DateTime,
+            MySqlColumnType::MYSQL_TYPE_YEAR => Self::Year,
+            MySqlColumnType::MYSQL_TYPE_NEWDATE => Self::NewDate,
+            MySqlColumnType::MYSQL_TYPE_VARCHAR => Self::VarChar,
+            MySqlColumnType::MYSQL_TYPE_BIT => Self::Bit,
+            MySqlColumnType::MYSQL_TYPE_TIMESTAMP2 => Self::Timestamp2,
+            MySqlColumnType::MYSQL_TYPE_DATETIME2 => Self::DateTime2,
+            MySqlColumnType::MYSQL_TYPE_TIME2 => Self::Time2,
+            MySqlColumnType::MYSQL_TYPE_JSON => Self::Json,
+            MySqlColumnType::MYSQL_TYPE_NEWDECIMAL => Self::NewDecimal,
+            MySqlColumnType::MYSQL_TYPE_ENUM => Self::Enum,
+            MySqlColumnType::MYSQL_TYPE_SET => Self::Set,
+            MySqlColumnType::MYSQL_TYPE_TINY_BLOB => Self::TinyBlob,
+            MySqlColumnType::MYSQL_TYPE_MEDIUM_BLOB => Self::MediumBlob,
+            MySqlColumnType::MYSQL_TYPE_LONG_BLOB => Self::LongBlob,
+            MySqlColumnType::MYSQL_TYPE_BLOB => Self::Blob,
+            MySqlColumnType::MYSQL_TYPE_VAR_STRING => Self::VarString,
+            MySqlColumnType::MYSQL_TYPE_STRING => Self::String,
+            MySqlColumnType::MYSQL_TYPE_GEOMETRY => Self::Geometry,
+        }
+    }
+}
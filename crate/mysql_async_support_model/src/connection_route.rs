@@ -0,0 +1,44 @@
+use ssh_jumper::model::{HostAddress, JumpHostAuthParams};
+
+use crate::JumpHost;
+
+/// How a query target's database server is reached.
+///
+/// Each route is prepared once per chunk of query targets, and yields the
+/// socket address to connect to for each target.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionRoute<'route> {
+    /// Connect to the database server directly.
+    ///
+    /// Use this when the database server is reachable from the local network.
+    Direct,
+    /// Connect to the database server through a chain of SSH jump hosts.
+    ///
+    /// Each jump host is connected to through the previous one, similar to
+    /// `ssh -J first_host,second_host`. Tunnels to the database servers are
+    /// opened from the last jump host.
+    SshJumpHosts(Vec<JumpHost<'route>>),
+}
+
+impl<'route> ConnectionRoute<'route> {
+    /// Returns a route through the given SSH jump host.
+    pub fn ssh_jump_host(
+        jump_host_address: HostAddress<'route>,
+        jump_host_auth_params: JumpHostAuthParams<'route>,
+    ) -> Self {
+        Self::SshJumpHosts(vec![JumpHost::new(
+            jump_host_address,
+            jump_host_auth_params,
+        )])
+    }
+
+    /// Returns an owned version of self.
+    pub fn into_static(self) -> ConnectionRoute<'static> {
+        match self {
+            Self::Direct => ConnectionRoute::Direct,
+            Self::SshJumpHosts(jump_hosts) => ConnectionRoute::SshJumpHosts(
+                jump_hosts.into_iter().map(JumpHost::into_static).collect(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::{borrow::Cow, path::Path, time::Duration};
+
+use mysql_async::Compression;
+
+use crate::SslMode;
+
+/// Options for connections to the database server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DbConnOpts<'opts> {
+    /// Whether and how to secure connections to the database server.
+    pub ssl_mode: SslMode,
+    /// Path to the CA certificate to verify the server certificate against.
+    ///
+    /// When `None`, the system's root certificates are used.
+    pub ssl_ca_path: Option<Cow<'opts, Path>>,
+    /// Path to the PKCS #12 archive with the client certificate and key.
+    pub ssl_client_pkcs12_path: Option<Cow<'opts, Path>>,
+    /// Password to decrypt the PKCS #12 archive.
+    pub ssl_client_pkcs12_password: Option<Cow<'opts, str>>,
+    /// Minimum number of connections that each pool keeps open.
+    pub pool_min: usize,
+    /// Maximum number of connections that each pool opens.
+    pub pool_max: usize,
+    /// Compression level of the connection, `None` to disable compression.
+    pub compression: Option<Compression>,
+    /// Interval between TCP keepalive probes, `None` to disable keepalive.
+    pub tcp_keepalive: Option<Duration>,
+}
+
+impl<'opts> DbConnOpts<'opts> {
+    /// Default maximum number of connections that each pool opens.
+    pub const POOL_MAX_DEFAULT: usize = 2;
+    /// Default minimum number of connections that each pool keeps open.
+    pub const POOL_MIN_DEFAULT: usize = 1;
+
+    /// Returns new `DbConnOpts` with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether and how to secure connections to the database server.
+    pub fn with_ssl_mode(mut self, ssl_mode: SslMode) -> Self {
+        self.ssl_mode = ssl_mode;
+        self
+    }
+
+    /// Sets the path to the CA certificate to verify the server certificate
+    /// against.
+    pub fn with_ssl_ca_path(mut self, ssl_ca_path: Cow<'opts, Path>) -> Self {
+        self.ssl_ca_path = Some(ssl_ca_path);
+        self
+    }
+
+    /// Sets the client certificate and key to present to the server.
+    ///
+    /// # Parameters
+    ///
+    /// * `pkcs12_path`: Path to the PKCS #12 archive.
+    /// * `password`: Password to decrypt the archive, if any.
+    pub fn with_ssl_client_pkcs12(
+        mut self,
+        pkcs12_path: Cow<'opts, Path>,
+        password: Option<Cow<'opts, str>>,
+    ) -> Self {
+        self.ssl_client_pkcs12_path = Some(pkcs12_path);
+        self.ssl_client_pkcs12_password = password;
+        self
+    }
+
+    /// Sets the minimum and maximum number of connections per pool.
+    pub fn with_pool_constraints(mut self, pool_min: usize, pool_max: usize) -> Self {
+        self.pool_min = pool_min;
+        self.pool_max = pool_max;
+        self
+    }
+
+    /// Sets the compression level of the connection.
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the interval between TCP keepalive probes.
+    pub fn with_tcp_keepalive(mut self, tcp_keepalive: Option<Duration>) -> Self {
+        self.tcp_keepalive = tcp_keepalive;
+        self
+    }
+
+    /// Returns an owned version of self.
+    pub fn into_static(self) -> DbConnOpts<'static> {
+        let DbConnOpts::<'opts> {
+            ssl_mode,
+            ssl_ca_path,
+            ssl_client_pkcs12_path,
+            ssl_client_pkcs12_password,
+            pool_min,
+            pool_max,
+            compression,
+            tcp_keepalive,
+        } = self;
+        let ssl_ca_path = ssl_ca_path.map(Cow::into_owned).map(Cow::Owned);
+        let ssl_client_pkcs12_path = ssl_client_pkcs12_path.map(Cow::into_owned).map(Cow::Owned);
+        let ssl_client_pkcs12_password = ssl_client_pkcs12_password
+            .map(Cow::into_owned)
+            .map(Cow::Owned);
+
+        DbConnOpts {
+            ssl_mode,
+            ssl_ca_path,
+            ssl_client_pkcs12_path,
+            ssl_client_pkcs12_password,
+            pool_min,
+            pool_max,
+            compression,
+            tcp_keepalive,
+        }
+    }
+}
+
+impl<'opts> Default for DbConnOpts<'opts> {
+    fn default() -> Self {
+        Self {
+            ssl_mode: SslMode::default(),
+            ssl_ca_path: None,
+            ssl_client_pkcs12_path: None,
+            ssl_client_pkcs12_password: None,
+            pool_min: Self::POOL_MIN_DEFAULT,
+            pool_max: Self::POOL_MAX_DEFAULT,
+            compression: None,
+            tcp_keepalive: None,
+        }
+    }
+}
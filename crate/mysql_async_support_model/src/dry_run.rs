@@ -0,0 +1,37 @@
+use std::borrow::Cow;
+
+/// Settings to run queries inside a transaction that is always rolled back.
+///
+/// This reports the number of rows a write would affect without changing any
+/// data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DryRun<'s> {
+    /// Query that selects the rows touched by the write, such as a `SELECT`
+    /// with the same `WHERE` clause.
+    ///
+    /// This is run without parameters in the same transaction before the
+    /// write, and its result sets are returned as the before-image.
+    pub before_image_sql: Option<Cow<'s, str>>,
+}
+
+impl<'s> DryRun<'s> {
+    /// Returns a new `DryRun` that does not select a before-image.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the query that selects the rows touched by the write.
+    pub fn with_before_image_sql(mut self, before_image_sql: Cow<'s, str>) -> Self {
+        self.before_image_sql = Some(before_image_sql);
+        self
+    }
+
+    /// Returns a `DryRun` with a `'static` lifetime.
+    pub fn into_static(self) -> DryRun<'static> {
+        DryRun {
+            before_image_sql: self
+                .before_image_sql
+                .map(|before_image_sql| Cow::Owned(before_image_sql.into_owned())),
+        }
+    }
+}
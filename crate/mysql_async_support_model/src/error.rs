@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use ssh_jumper::model::HostAddress;
 
@@ -15,6 +15,16 @@ pub enum Error {
     MySqlPrepare(mysql_async::Error),
     /// Failed to execute SQL query.
     MySqlExecute(mysql_async::Error),
+    /// Query parameters were given for a query sent with the text protocol.
+    QueryParamsTextProtocol,
+    /// Failed to start a transaction.
+    TransactionBegin(mysql_async::Error),
+    /// Failed to prepare a transaction to be committed.
+    TransactionPrepare(mysql_async::Error),
+    /// Failed to commit a transaction.
+    TransactionCommit(mysql_async::Error),
+    /// Failed to roll back a transaction.
+    TransactionRollback(mysql_async::Error),
     /// Failed to fetch result set from query execution.
     ///
     /// One query may have multiple result sets, and we may fail to fetch a
@@ -24,15 +34,97 @@ pub enum Error {
     MySqlPoolDisconnect(mysql_async::Error),
     /// SSH connection initialization failed.
     SshConnInit,
+    /// SSH connection route did not specify any jump hosts.
+    SshJumpHostsEmpty,
+    /// Failed to connect to a jump host in an SSH jump host chain.
+    SshJumpHostConnect {
+        /// Position of the jump host in the chain, starting from `1`.
+        hop_number: usize,
+        /// Address of the jump host.
+        jump_host_address: HostAddress<'static>,
+        /// The underlying error.
+        error: Box<ssh_jumper::model::Error>,
+    },
+    /// SSH session was lost, and could not be re-established.
+    SshReconnect {
+        /// Address of the last jump host in the chain.
+        jump_host_address: HostAddress<'static>,
+        /// Number of reconnection attempts made.
+        attempts: u32,
+        /// Error from the last reconnection attempt, if any were made.
+        error: Option<Box<Error>>,
+    },
     /// SSH tunnel was not found for a query target.
     SshTunnelNotFound {
         /// Address of the jump host.
         jump_host_address: HostAddress<'static>,
         /// The query target that the SSH tunnel wasn't found for.
-        query_target: QueryTarget<'static>,
+        query_target: Box<QueryTarget<'static>>,
     },
     /// Error while using the `ssh_jumper` crate.
     SshJumper(Box<ssh_jumper::model::Error>),
+    /// Timed out opening SSH sessions through the jump hosts.
+    SshConnectTimeout {
+        /// Address of the last jump host in the chain.
+        jump_host_address: HostAddress<'static>,
+        /// The timeout that elapsed.
+        duration: Duration,
+    },
+    /// Timed out opening a tunnel channel to the database server.
+    TunnelOpenTimeout {
+        /// The timeout that elapsed.
+        duration: Duration,
+    },
+    /// Timed out getting a connection to the database server.
+    DbConnectTimeout {
+        /// The timeout that elapsed.
+        duration: Duration,
+    },
+    /// Timed out executing a query and fetching its results.
+    QueryTimeout {
+        /// The timeout that elapsed.
+        duration: Duration,
+    },
+    /// Timed out before all phases of a query target completed.
+    TargetTimeout {
+        /// The timeout that elapsed.
+        duration: Duration,
+    },
+    /// The query was cancelled before it completed.
+    QueryCancelled,
+    /// Failed to kill a running query.
+    QueryKill(mysql_async::Error),
+    /// A phase failed on every attempt allowed by its retry policy.
+    ///
+    /// Any failure after more than one attempt is wrapped in this variant, so
+    /// matching on the error from the last attempt, such as
+    /// [`Error::QueryTimeout`], needs [`Error::error_last_attempt`].
+    RetryExhausted {
+        /// Number of attempts made.
+        attempts: u32,
+        /// Error from the last attempt.
+        error: Box<Error>,
+    },
+    /// The operation or statement cannot be run as a dry run.
+    ///
+    /// This is returned instead of running the queries when `dry_run` is set,
+    /// as they would otherwise change data, such as when the operation does
+    /// not run its queries in a transaction, or a statement commits or ends
+    /// the transaction. See [`SqlStatement::ends_transaction`].
+    ///
+    /// [`SqlStatement::ends_transaction`]: crate::SqlStatement::ends_transaction
+    DryRunUnsupported,
+}
+
+impl Error {
+    /// Returns the error from the last attempt if this is
+    /// [`Error::RetryExhausted`], otherwise returns this error.
+    pub fn error_last_attempt(&self) -> &Error {
+        match self {
+            Self::RetryExhausted { error, .. } => error.as_ref(),
+            error => error,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -44,22 +136,91 @@ impl fmt::Display for Error {
             Self::MySqlConnectionRetrieve(..) => write!(f, "Failed to get MySql connection."),
             Self::MySqlPrepare(..) => write!(f, "Failed to prepare SQL statement."),
             Self::MySqlExecute(..) => write!(f, "Failed to execute SQL query."),
+            Self::QueryParamsTextProtocol => write!(
+                f,
+                "Query parameters cannot be bound when using the text protocol."
+            ),
+            Self::TransactionBegin(..) => write!(f, "Failed to start transaction."),
+            Self::TransactionPrepare(..) => write!(f, "Failed to prepare transaction."),
+            Self::TransactionCommit(..) => write!(f, "Failed to commit transaction."),
+            Self::TransactionRollback(..) => write!(f, "Failed to roll back transaction."),
             Self::QueryResultSetFetch(..) => write!(f, "Failed to fetch next query result set."),
             Self::MySqlPoolDisconnect(..) => {
                 write!(f, "Failed to cleanly disconnect MySQL connection pool.")
             }
             Self::SshConnInit => write!(f, "SSH connection initialization failed."),
+            Self::SshJumpHostsEmpty => {
+                write!(f, "SSH connection route must have at least one jump host.")
+            }
+            Self::SshJumpHostConnect {
+                hop_number,
+                jump_host_address,
+                error,
+            } => write!(
+                f,
+                "Failed to connect to jump host {hop_number}: `{jump_host}`. {error}",
+                hop_number = hop_number,
+                jump_host = jump_host_address,
+                error = error
+            ),
+            Self::SshReconnect {
+                jump_host_address,
+                attempts,
+                ..
+            } => write!(
+                f,
+                "SSH session through jump host `{jump_host}` was lost, and could not be re-established after {attempts} attempts.",
+                jump_host = jump_host_address,
+                attempts = attempts
+            ),
             Self::SshTunnelNotFound {
                 jump_host_address,
                 query_target,
             } => write!(
                 f,
-                "Expected SSH tunnel for `{query_target}: {db_address}` to be established through jump host: `{jump_host}`. This is likely a bug.",
+                "Expected SSH tunnel for `{query_target}: {db_address}:{db_port}` to be established through jump host: `{jump_host}`. This is likely a bug.",
                 query_target = query_target.name,
                 db_address = query_target.db_address,
+                db_port = query_target.db_port,
                 jump_host = jump_host_address
             ),
             Self::SshJumper(error) => error.fmt(f),
+            Self::SshConnectTimeout {
+                jump_host_address,
+                duration,
+            } => write!(
+                f,
+                "Timed out after {duration:?} connecting through jump host `{jump_host}`.",
+                duration = duration,
+                jump_host = jump_host_address
+            ),
+            Self::TunnelOpenTimeout { duration } => write!(
+                f,
+                "Timed out after {:?} opening tunnel to database server.",
+                duration
+            ),
+            Self::DbConnectTimeout { duration } => write!(
+                f,
+                "Timed out after {:?} connecting to database server.",
+                duration
+            ),
+            Self::QueryTimeout { duration } => {
+                write!(f, "Timed out after {:?} executing query.", duration)
+            }
+            Self::TargetTimeout { duration } => write!(
+                f,
+                "Timed out after {:?} before the query target completed.",
+                duration
+            ),
+            Self::QueryCancelled => write!(f, "Query was cancelled."),
+            Self::QueryKill(..) => write!(f, "Failed to kill running query."),
+            Self::RetryExhausted { attempts, .. } => {
+                write!(f, "Failed after {} attempts.", attempts)
+            }
+            Self::DryRunUnsupported => write!(
+                f,
+                "Dry run is not supported by this operation or statement, so the queries were not run."
+            ),
         }
     }
 }
@@ -71,11 +232,30 @@ impl std::error::Error for Error {
             Self::MySqlConnectionRetrieve(error) => Some(error),
             Self::MySqlPrepare(error) => Some(error),
             Self::MySqlExecute(error) => Some(error),
+            Self::QueryParamsTextProtocol => None,
+            Self::TransactionBegin(error) => Some(error),
+            Self::TransactionPrepare(error) => Some(error),
+            Self::TransactionCommit(error) => Some(error),
+            Self::TransactionRollback(error) => Some(error),
             Self::QueryResultSetFetch(error) => Some(error),
             Self::MySqlPoolDisconnect(error) => Some(error),
             Self::SshConnInit => None,
+            Self::SshJumpHostsEmpty => None,
+            Self::SshJumpHostConnect { error, .. } => Some(error.as_ref()),
+            Self::SshReconnect { error, .. } => error
+                .as_deref()
+                .map(|error| error as &(dyn std::error::Error + 'static)),
             Self::SshTunnelNotFound { .. } => None,
             Self::SshJumper(error) => error.source(),
+            Self::SshConnectTimeout { .. } => None,
+            Self::TunnelOpenTimeout { .. } => None,
+            Self::DbConnectTimeout { .. } => None,
+            Self::QueryTimeout { .. } => None,
+            Self::TargetTimeout { .. } => None,
+            Self::QueryCancelled => None,
+            Self::QueryKill(error) => Some(error),
+            Self::RetryExhausted { error, .. } => Some(error.as_ref()),
+            Self::DryRunUnsupported => None,
         }
     }
 }
@@ -85,3 +265,53 @@ impl From<ssh_jumper::model::Error> for Error {
         Self::SshJumper(Box::new(error))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Duration,
+    };
+
+    use ssh_jumper::model::HostAddress;
+
+    use super::Error;
+
+    #[test]
+    fn display_timeouts() {
+        let duration = Duration::from_secs(5);
+
+        assert_eq!(
+            "Timed out after 5s connecting through jump host `10.0.0.1`.",
+            Error::SshConnectTimeout {
+                jump_host_address: HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+                duration,
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "Timed out after 5s opening tunnel to database server.",
+            Error::TunnelOpenTimeout { duration }.to_string()
+        );
+        assert_eq!(
+            "Timed out after 5s connecting to database server.",
+            Error::DbConnectTimeout { duration }.to_string()
+        );
+        assert_eq!(
+            "Timed out after 5s executing query.",
+            Error::QueryTimeout { duration }.to_string()
+        );
+        assert_eq!(
+            "Timed out after 5s before the query target completed.",
+            Error::TargetTimeout { duration }.to_string()
+        );
+    }
+
+    #[test]
+    fn timeouts_have_no_source() {
+        let duration = Duration::from_secs(5);
+
+        assert!(std::error::Error::source(&Error::QueryTimeout { duration }).is_none());
+        assert!(std::error::Error::source(&Error::TargetTimeout { duration }).is_none());
+    }
+}
@@ -0,0 +1,95 @@
+use std::borrow::Cow;
+
+use ssh_jumper::model::{HostAddress, JumpHostAuthParams};
+
+/// SSH jump host and the parameters to authenticate with it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JumpHost<'jump_host> {
+    /// Address of the jump host.
+    pub address: HostAddress<'jump_host>,
+    /// Port that the jump host listens on for SSH connections.
+    pub port: u16,
+    /// SSH authentication parameters for the jump host.
+    pub auth_params: JumpHostAuthParams<'jump_host>,
+}
+
+impl<'jump_host> JumpHost<'jump_host> {
+    /// Default port that jump hosts listen on for SSH connections.
+    pub const PORT_DEFAULT: u16 = 22;
+
+    /// Returns a new `JumpHost` that listens on the default SSH port.
+    pub fn new(
+        address: HostAddress<'jump_host>,
+        auth_params: JumpHostAuthParams<'jump_host>,
+    ) -> Self {
+        Self {
+            address,
+            port: Self::PORT_DEFAULT,
+            auth_params,
+        }
+    }
+
+    /// Sets the port that the jump host listens on for SSH connections.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Returns an owned version of self.
+    pub fn into_static(self) -> JumpHost<'static> {
+        let JumpHost::<'jump_host> {
+            address,
+            port,
+            auth_params,
+        } = self;
+        let address = address.into_static();
+        let JumpHostAuthParams {
+            user_name,
+            private_key,
+            passphrase,
+        } = auth_params;
+        let auth_params = JumpHostAuthParams {
+            user_name: Cow::Owned(user_name.into_owned()),
+            private_key: Cow::Owned(private_key.into_owned()),
+            passphrase: passphrase.map(Cow::into_owned).map(Cow::Owned),
+        };
+
+        JumpHost {
+            address,
+            port,
+            auth_params,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        net::{IpAddr, Ipv4Addr},
+        path::Path,
+    };
+
+    use ssh_jumper::model::{HostAddress, JumpHostAuthParams};
+
+    use super::JumpHost;
+
+    fn jump_host() -> JumpHost<'static> {
+        JumpHost::new(
+            HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            JumpHostAuthParams::new(Cow::Borrowed("user"), Cow::Borrowed(Path::new("id_rsa"))),
+        )
+    }
+
+    #[test]
+    fn port_defaults_to_ssh_port() {
+        assert_eq!(JumpHost::PORT_DEFAULT, jump_host().port);
+    }
+
+    #[test]
+    fn with_port_is_kept_by_into_static() {
+        let jump_host = jump_host().with_port(2222).into_static();
+
+        assert_eq!(2222, jump_host.port);
+    }
+}
@@ -1,19 +1,62 @@
+pub use bigdecimal::BigDecimal;
+
 pub use crate::{
-    db_schema_cred::DbSchemaCred, error::Error, query_error::QueryError, query_result::QueryResult,
-    query_target::QueryTarget, result_set::ResultSet, result_set_strings::ResultSetStrings,
-    result_set_typed::ResultSetTyped, ssh_tunnel_map::SshTunnelMap, string_values::StringValues,
+    column::Column, column_flags::ColumnFlags, column_type::ColumnType,
+    connection_route::ConnectionRoute, db_conn_opts::DbConnOpts, db_schema_cred::DbSchemaCred,
+    dry_run::DryRun, error::Error, jump_host::JumpHost, query_error::QueryError,
+    query_params::QueryParams, query_protocol::QueryProtocol, query_result::QueryResult,
+    query_target::QueryTarget, result_limits::ResultLimits, result_set::ResultSet,
+    result_set_strings::ResultSetStrings, result_set_typed::ResultSetTyped,
+    retry_policies::RetryPolicies, retry_policy::RetryPolicy, retryable_error::RetryableError,
+    route_map::RouteMap, row_column::RowColumn, row_stream_item::RowStreamItem,
+    row_values::RowValues, script_error::ScriptError, script_error_policy::ScriptErrorPolicy,
+    script_result::ScriptResult, sql_script::SqlScript, sql_statement::SqlStatement,
+    ssh_session_chain::SshSessionChain, ssh_tunnel_listener::SshTunnelListener,
+    ssh_tunnel_map::SshTunnelMap, ssl_mode::SslMode, statement_result::StatementResult,
+    string_values::StringValues, timeouts::Timeouts, transaction_mode::TransactionMode,
+    transaction_outcome::TransactionOutcome, transaction_result::TransactionResult,
     typed_values::TypedValues, value::Value,
 };
 
+mod column;
+mod column_flags;
+mod column_type;
+mod connection_route;
+mod db_conn_opts;
 mod db_schema_cred;
+mod dry_run;
 mod error;
+mod jump_host;
 mod query_error;
+mod query_params;
+mod query_protocol;
 mod query_result;
 mod query_target;
+mod result_limits;
 mod result_set;
 mod result_set_strings;
 mod result_set_typed;
+mod retry_policies;
+mod retry_policy;
+mod retryable_error;
+mod route_map;
+mod row_column;
+mod row_stream_item;
+mod row_values;
+mod script_error;
+mod script_error_policy;
+mod script_result;
+mod sql_script;
+mod sql_statement;
+mod ssh_session_chain;
+mod ssh_tunnel_listener;
 mod ssh_tunnel_map;
+mod ssl_mode;
+mod statement_result;
 mod string_values;
+mod timeouts;
+mod transaction_mode;
+mod transaction_outcome;
+mod transaction_result;
 mod typed_values;
 mod value;
@@ -1,14 +1,31 @@
-use crate::Error;
+use crate::{Error, QueryTarget};
 
 /// Query target name and error when attempting to run the query.
 #[derive(Debug)]
 pub struct QueryError {
     /// Name of the query target.
     pub name: String,
+    /// Port of the query target's database server.
+    pub db_port: u16,
     /// The error that occurred when running the query.
+    ///
+    /// If the query was attempted more than once, this is
+    /// [`Error::RetryExhausted`], and [`Error::error_last_attempt`] returns the
+    /// error to match on.
     pub error: Error,
 }
 
+impl QueryError {
+    /// Returns a new `QueryError` for the query target.
+    pub fn new(query_target: &QueryTarget<'_>, error: Error) -> Self {
+        Self {
+            name: query_target.name.to_string(),
+            db_port: query_target.db_port,
+            error,
+        }
+    }
+}
+
 impl std::ops::Deref for QueryError {
     type Target = Error;
 
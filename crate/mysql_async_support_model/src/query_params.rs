@@ -0,0 +1,59 @@
+use indexmap::IndexMap;
+use mysql_async::{Params, Value as MySqlValue};
+
+use crate::Value;
+
+/// Parameters to bind to a prepared statement.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum QueryParams {
+    /// The statement has no parameters.
+    #[default]
+    Empty,
+    /// Values for `?` placeholders, in order.
+    Positional(Vec<Value>),
+    /// Values for `:name` placeholders, by name.
+    Named(IndexMap<String, Value>),
+}
+
+impl QueryParams {
+    /// Returns whether there are no parameters to bind.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Empty => true,
+            Self::Positional(values) => values.is_empty(),
+            Self::Named(values) => values.is_empty(),
+        }
+    }
+}
+
+impl From<Vec<Value>> for QueryParams {
+    fn from(values: Vec<Value>) -> Self {
+        Self::Positional(values)
+    }
+}
+
+impl From<IndexMap<String, Value>> for QueryParams {
+    fn from(values: IndexMap<String, Value>) -> Self {
+        Self::Named(values)
+    }
+}
+
+impl From<QueryParams> for Params {
+    fn from(query_params: QueryParams) -> Self {
+        match query_params {
+            QueryParams::Empty => Params::Empty,
+            QueryParams::Positional(values) => {
+                Params::from(values.into_iter().map(MySqlValue::from).collect::<Vec<_>>())
+            }
+            // Names are unique in the map, so `Params::from` will not panic on redefined
+            // names.
+            QueryParams::Named(values) if values.is_empty() => Params::Empty,
+            QueryParams::Named(values) => Params::from(
+                values
+                    .into_iter()
+                    .map(|(name, value)| (name, MySqlValue::from(value)))
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
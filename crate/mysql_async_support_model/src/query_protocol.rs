@@ -0,0 +1,25 @@
+/// Protocol used to send queries to the database server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QueryProtocol {
+    /// Prepare the statement, and execute it with the binary protocol.
+    ///
+    /// Values are returned with their column types, and parameters may be
+    /// bound.
+    #[default]
+    Binary,
+    /// Send the SQL text as is with the text protocol.
+    ///
+    /// This supports multiple statements in one query, and statements that
+    /// cannot be prepared, such as `LOCK TABLES` or `CREATE PROCEDURE`.
+    /// Values are returned as bytes, and parameters cannot be bound.
+    Text,
+    /// Use the binary protocol, falling back to the text protocol when the
+    /// server refuses to prepare the statement.
+    ///
+    /// The fallback is used when the server does not support preparing the
+    /// statement (`ER_UNSUPPORTED_PS`), or reports a parse error
+    /// (`ER_PARSE_ERROR`) for SQL text with multiple statements. Other errors
+    /// are returned as is, and the fallback is not used when there are
+    /// parameters to bind.
+    Auto,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::ResultSet;
+use crate::{QueryTarget, ResultSet};
 
 /// Query target name and result sets.
 ///
@@ -14,6 +14,53 @@ use crate::ResultSet;
 pub struct QueryResult<T> {
     /// Name of the query target.
     pub name: String,
+    /// Port of the query target's database server.
+    ///
+    /// This distinguishes database servers that share an address. Results
+    /// serialized without a port are read with the default MySQL port.
+    #[serde(default = "QueryResult::<T>::db_port_default")]
+    pub db_port: u16,
     /// Result sets returned by the query.
     pub result_sets: Vec<ResultSet<T>>,
+    /// Result sets of the [`before_image_sql`] query, when run as a dry run.
+    ///
+    /// [`before_image_sql`]: crate::DryRun::before_image_sql
+    #[serde(default)]
+    pub before_image: Option<Vec<ResultSet<T>>>,
+    /// Number of attempts made to execute the query, including the first.
+    ///
+    /// This counts attempts under the [`query`] retry policy only. Retries of
+    /// the [`ssh_setup`] and [`db_connect`] phases are not counted here, as
+    /// SSH setup is shared by every query target on the route. When those
+    /// phases fail after more than one attempt, the error is
+    /// [`Error::RetryExhausted`] with their attempt count.
+    ///
+    /// [`query`]: crate::RetryPolicies::query
+    /// [`ssh_setup`]: crate::RetryPolicies::ssh_setup
+    /// [`db_connect`]: crate::RetryPolicies::db_connect
+    /// [`Error::RetryExhausted`]: crate::Error::RetryExhausted
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+impl<T> QueryResult<T> {
+    fn db_port_default() -> u16 {
+        QueryTarget::DB_PORT_DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryResult;
+    use crate::QueryTarget;
+
+    #[test]
+    fn db_port_defaults_when_not_serialized() {
+        let query_result = serde_json::from_str::<QueryResult<u32>>(
+            r#"{"name":"db","result_sets":[],"before_image":null}"#,
+        )
+        .expect("Failed to deserialize `QueryResult`.");
+
+        assert_eq!(QueryTarget::DB_PORT_DEFAULT, query_result.db_port);
+    }
 }
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use ssh_jumper::model::HostAddress;
+use ssh_jumper::model::{HostAddress, HostSocketParams};
 
-use crate::DbSchemaCred;
+use crate::{ConnectionRoute, DbConnOpts, DbSchemaCred};
 
 /// An entity for which to run a query.
 #[derive(Clone, Debug, PartialEq)]
@@ -13,26 +13,144 @@ pub struct QueryTarget<'query> {
     pub name: Cow<'query, str>,
     /// Address of the database server.
     pub db_address: HostAddress<'query>,
+    /// Port that the database server listens on.
+    pub db_port: u16,
     /// DB Schema and credentials of the database.
     pub db_schema_cred: DbSchemaCred<'query>,
+    /// Route to reach this target's database server.
+    ///
+    /// When `None`, the route passed to the query runner is used.
+    pub connection_route: Option<ConnectionRoute<'query>>,
+    /// Options for connections to this target's database server.
+    ///
+    /// When `None`, the options of the query runner are used.
+    pub db_conn_opts: Option<DbConnOpts<'query>>,
 }
 
 impl<'query> QueryTarget<'query> {
+    /// Default port that MySQL servers listen on.
+    pub const DB_PORT_DEFAULT: u16 = 3306;
+
+    /// Returns a new `QueryTarget`.
+    ///
+    /// The database port is defaulted to [`Self::DB_PORT_DEFAULT`]. Use the
+    /// [`with_db_port`][`Self::with_db_port`] method if you would like to
+    /// change this.
+    pub fn new(
+        name: Cow<'query, str>,
+        db_address: HostAddress<'query>,
+        db_schema_cred: DbSchemaCred<'query>,
+    ) -> Self {
+        Self {
+            name,
+            db_address,
+            db_port: Self::DB_PORT_DEFAULT,
+            db_schema_cred,
+            connection_route: None,
+            db_conn_opts: None,
+        }
+    }
+
+    /// Sets the port that the database server listens on.
+    pub fn with_db_port(mut self, db_port: u16) -> Self {
+        self.db_port = db_port;
+        self
+    }
+
+    /// Sets the route to reach this target's database server.
+    ///
+    /// This overrides the route passed to the query runner.
+    pub fn with_connection_route(mut self, connection_route: ConnectionRoute<'query>) -> Self {
+        self.connection_route = Some(connection_route);
+        self
+    }
+
+    /// Sets the options for connections to this target's database server.
+    ///
+    /// This overrides the options of the query runner.
+    pub fn with_db_conn_opts(mut self, db_conn_opts: DbConnOpts<'query>) -> Self {
+        self.db_conn_opts = Some(db_conn_opts);
+        self
+    }
+
+    /// Returns the address and port of the database server.
+    pub fn db_socket(&self) -> HostSocketParams<'query> {
+        HostSocketParams {
+            address: self.db_address.clone(),
+            port: self.db_port,
+        }
+    }
+
     /// Returns an owned version of self.
     pub fn into_static(self) -> QueryTarget<'static> {
         let QueryTarget::<'query> {
             name,
             db_address,
+            db_port,
             db_schema_cred,
+            connection_route,
+            db_conn_opts,
         } = self;
         let name = Cow::Owned(name.into_owned());
         let db_address = db_address.into_static();
         let db_schema_cred = db_schema_cred.into_static();
+        let connection_route = connection_route.map(ConnectionRoute::into_static);
+        let db_conn_opts = db_conn_opts.map(DbConnOpts::into_static);
 
         QueryTarget {
             name,
             db_address,
+            db_port,
             db_schema_cred,
+            connection_route,
+            db_conn_opts,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        net::{IpAddr, Ipv4Addr},
+    };
+
+    use ssh_jumper::model::{HostAddress, HostSocketParams};
+
+    use super::QueryTarget;
+    use crate::DbSchemaCred;
+
+    fn query_target() -> QueryTarget<'static> {
+        QueryTarget::new(
+            Cow::Borrowed("target"),
+            HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            DbSchemaCred {
+                schema_name: None,
+                username: Cow::Borrowed("user"),
+                password: Cow::Borrowed("pass"),
+            },
+        )
+    }
+
+    #[test]
+    fn db_socket_uses_default_port() {
+        assert_eq!(
+            HostSocketParams {
+                address: HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+                port: 3306,
+            },
+            query_target().db_socket()
+        );
+    }
+
+    #[test]
+    fn db_socket_uses_db_port() {
+        assert_eq!(
+            HostSocketParams {
+                address: HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+                port: 3307,
+            },
+            query_target().with_db_port(3307).db_socket()
+        );
+    }
+}
@@ -0,0 +1,30 @@
+/// Limits on the amount of data kept from a query target's results.
+///
+/// A `None` limit is not enforced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// Maximum number of rows kept per result set.
+    pub rows_per_result_set: Option<usize>,
+    /// Maximum number of bytes of row values kept per query target, across
+    /// all of its result sets.
+    pub bytes_per_target: Option<u64>,
+}
+
+impl ResultLimits {
+    /// Returns `ResultLimits` where no limit is enforced.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of rows kept per result set.
+    pub fn with_rows_per_result_set(mut self, rows_per_result_set: Option<usize>) -> Self {
+        self.rows_per_result_set = rows_per_result_set;
+        self
+    }
+
+    /// Sets the maximum number of bytes of row values kept per query target.
+    pub fn with_bytes_per_target(mut self, bytes_per_target: Option<u64>) -> Self {
+        self.bytes_per_target = bytes_per_target;
+        self
+    }
+}
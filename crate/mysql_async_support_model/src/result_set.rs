@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::Column;
+
 /// Message, warning count, and result values for a single statement.
 ///
 /// See [`ResultSetStrings`] and [`ResultSetTyped`] for aliased versions of this
@@ -20,6 +22,17 @@ pub struct ResultSet<T> {
     pub warning_count: u16,
     /// Message returned by the server.
     pub info: String,
+    /// Descriptors of the columns in each row, in order.
+    ///
+    /// This is empty for statements that do not return rows.
+    #[serde(default)]
+    pub columns: Vec<Column>,
     /// Values returned by the statement.
     pub values: Vec<T>,
+    /// Number of rows kept when the result set was truncated by a
+    /// [`ResultLimits`] limit, `None` if every row was kept.
+    ///
+    /// [`ResultLimits`]: crate::ResultLimits
+    #[serde(default)]
+    pub truncated_at: Option<usize>,
 }
@@ -0,0 +1,46 @@
+use crate::RetryPolicy;
+
+/// Retry policy for each phase of running queries on a query target.
+///
+/// By default, no phase is retried.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RetryPolicies {
+    /// Retry policy to open SSH sessions and tunnels.
+    pub ssh_setup: RetryPolicy,
+    /// Retry policy to get a connection to the database server.
+    pub db_connect: RetryPolicy,
+    /// Retry policy to execute a query and fetch its results.
+    ///
+    /// A query that times out or loses its connection may still have been
+    /// applied, so [`RetryableError::Timeout`] and [`RetryableError::Io`] are
+    /// only safe here for idempotent statements. [`RetryPolicy::query`] only
+    /// retries deadlocks and lock wait timeouts.
+    ///
+    /// [`RetryableError::Timeout`]: crate::RetryableError::Timeout
+    /// [`RetryableError::Io`]: crate::RetryableError::Io
+    pub query: RetryPolicy,
+}
+
+impl RetryPolicies {
+    /// Sets the retry policy to open SSH sessions and tunnels.
+    pub fn with_ssh_setup(mut self, ssh_setup: RetryPolicy) -> Self {
+        self.ssh_setup = ssh_setup;
+        self
+    }
+
+    /// Sets the retry policy to get a connection to the database server.
+    pub fn with_db_connect(mut self, db_connect: RetryPolicy) -> Self {
+        self.db_connect = db_connect;
+        self
+    }
+
+    /// Sets the retry policy to execute a query and fetch its results.
+    ///
+    /// See [`query`] for which errors are safe to retry.
+    ///
+    /// [`query`]: Self::query
+    pub fn with_query(mut self, query: RetryPolicy) -> Self {
+        self.query = query;
+        self
+    }
+}
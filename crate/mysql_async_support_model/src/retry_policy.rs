@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use crate::{Error, RetryableError};
+
+/// How to retry a phase that fails with a transient error.
+///
+/// Attempts are separated by an exponential backoff, starting at
+/// [`backoff_initial`] and doubling after each attempt up to [`backoff_max`].
+/// The actual delay is randomly chosen between half and all of the backoff, so
+/// that query targets that fail together do not retry together.
+///
+/// [`backoff_initial`]: Self::backoff_initial
+/// [`backoff_max`]: Self::backoff_max
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first.
+    ///
+    /// `1` disables retries.
+    pub max_attempts: u32,
+    /// Backoff before the second attempt.
+    pub backoff_initial: Duration,
+    /// Maximum backoff between attempts.
+    pub backoff_max: Duration,
+    /// Classes of errors that are retried.
+    pub retryable_errors: Vec<RetryableError>,
+}
+
+impl RetryPolicy {
+    /// Default backoff before the second attempt.
+    pub const BACKOFF_INITIAL_DEFAULT: Duration = Duration::from_millis(100);
+    /// Default maximum backoff between attempts.
+    pub const BACKOFF_MAX_DEFAULT: Duration = Duration::from_secs(10);
+    /// Default classes of errors that are retried when opening SSH sessions
+    /// and tunnels, and getting database connections.
+    ///
+    /// These phases do not change data, so timeouts and I/O errors are safe to
+    /// retry. Use [`RETRYABLE_ERRORS_QUERY_DEFAULT`] for the query phase.
+    ///
+    /// [`RETRYABLE_ERRORS_QUERY_DEFAULT`]: Self::RETRYABLE_ERRORS_QUERY_DEFAULT
+    pub const RETRYABLE_ERRORS_DEFAULT: &'static [RetryableError] = &[
+        RetryableError::Ssh,
+        RetryableError::Timeout,
+        RetryableError::Io,
+        RetryableError::DEADLOCK,
+        RetryableError::LOCK_WAIT_TIMEOUT,
+    ];
+    /// Default classes of errors that are retried when executing a query.
+    ///
+    /// Only deadlocks and lock wait timeouts are retried, as the server rolls
+    /// back the statement before returning them. After a [`Timeout`] or an
+    /// [`Io`] error, the statement may still have been applied, so these are
+    /// only safe to retry for idempotent statements.
+    ///
+    /// [`Timeout`]: RetryableError::Timeout
+    /// [`Io`]: RetryableError::Io
+    pub const RETRYABLE_ERRORS_QUERY_DEFAULT: &'static [RetryableError] =
+        &[RetryableError::DEADLOCK, RetryableError::LOCK_WAIT_TIMEOUT];
+
+    /// Returns a `RetryPolicy` with the given maximum number of attempts, and
+    /// default backoff and retryable errors.
+    ///
+    /// This is intended for opening SSH sessions and tunnels, and getting
+    /// database connections. Use [`RetryPolicy::query`] for the query phase.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            backoff_initial: Self::BACKOFF_INITIAL_DEFAULT,
+            backoff_max: Self::BACKOFF_MAX_DEFAULT,
+            retryable_errors: Self::RETRYABLE_ERRORS_DEFAULT.to_vec(),
+        }
+    }
+
+    /// Returns a `RetryPolicy` for executing queries, with the given maximum
+    /// number of attempts, and default backoff and query retryable errors.
+    ///
+    /// See [`RETRYABLE_ERRORS_QUERY_DEFAULT`] for which errors are retried.
+    ///
+    /// [`RETRYABLE_ERRORS_QUERY_DEFAULT`]: Self::RETRYABLE_ERRORS_QUERY_DEFAULT
+    pub fn query(max_attempts: u32) -> Self {
+        Self::new(max_attempts).with_retryable_errors(Self::RETRYABLE_ERRORS_QUERY_DEFAULT.to_vec())
+    }
+
+    /// Returns a `RetryPolicy` that does not retry.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Sets the backoff before the second attempt, and the maximum backoff
+    /// between attempts.
+    pub fn with_backoff(mut self, backoff_initial: Duration, backoff_max: Duration) -> Self {
+        self.backoff_initial = backoff_initial;
+        self.backoff_max = backoff_max;
+        self
+    }
+
+    /// Sets the classes of errors that are retried.
+    pub fn with_retryable_errors(mut self, retryable_errors: Vec<RetryableError>) -> Self {
+        self.retryable_errors = retryable_errors;
+        self
+    }
+
+    /// Returns whether to make another attempt after `attempt` failed with the
+    /// given error.
+    ///
+    /// `attempt` starts at 1 for the first attempt.
+    pub fn retry_should(&self, attempt: u32, error: &Error) -> bool {
+        attempt < self.max_attempts
+            && self
+                .retryable_errors
+                .iter()
+                .any(|retryable_error| retryable_error.matches(error))
+    }
+
+    /// Returns the backoff after `attempt` failed, before it is randomized.
+    ///
+    /// `attempt` starts at 1 for the first attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt.saturating_sub(1))
+            .and_then(|multiplier| self.backoff_initial.checked_mul(multiplier))
+            .map(|backoff| backoff.min(self.backoff_max))
+            .unwrap_or(self.backoff_max)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+    use crate::Error;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let retry_policy =
+            RetryPolicy::new(10).with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+        assert_eq!(Duration::from_millis(100), retry_policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), retry_policy.backoff(2));
+        assert_eq!(Duration::from_millis(800), retry_policy.backoff(4));
+        assert_eq!(Duration::from_secs(1), retry_policy.backoff(5));
+        assert_eq!(Duration::from_secs(1), retry_policy.backoff(40));
+    }
+
+    #[test]
+    fn retry_should_stops_at_max_attempts() {
+        let retry_policy = RetryPolicy::new(3);
+        let error = Error::QueryTimeout {
+            duration: Duration::from_secs(1),
+        };
+
+        assert!(retry_policy.retry_should(1, &error));
+        assert!(retry_policy.retry_should(2, &error));
+        assert!(!retry_policy.retry_should(3, &error));
+        assert!(!retry_policy.retry_should(1, &Error::QueryCancelled));
+    }
+
+    #[test]
+    fn query_does_not_retry_timeout() {
+        let retry_policy = RetryPolicy::query(3);
+        let error = Error::QueryTimeout {
+            duration: Duration::from_secs(1),
+        };
+
+        assert!(!retry_policy.retry_should(1, &error));
+    }
+}
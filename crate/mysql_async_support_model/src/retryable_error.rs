@@ -0,0 +1,88 @@
+use crate::Error;
+
+/// Class of error that may succeed if the failed phase is attempted again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryableError {
+    /// Failures to open SSH sessions or tunnels.
+    Ssh,
+    /// Phase timeouts, other than the [`target`] timeout.
+    ///
+    /// [`target`]: crate::Timeouts::target
+    Timeout,
+    /// I/O errors communicating with the database server, such as a dropped
+    /// connection.
+    Io,
+    /// Database server errors with the given error code.
+    ServerCode(u16),
+}
+
+impl RetryableError {
+    /// Deadlock found when trying to get lock (`ER_LOCK_DEADLOCK`).
+    pub const DEADLOCK: Self = Self::ServerCode(1213);
+    /// Lock wait timeout exceeded (`ER_LOCK_WAIT_TIMEOUT`).
+    pub const LOCK_WAIT_TIMEOUT: Self = Self::ServerCode(1205);
+
+    /// Returns whether the error belongs to this class.
+    pub fn matches(self, error: &Error) -> bool {
+        match self {
+            Self::Ssh => matches!(
+                error,
+                Error::SshJumper(..)
+                    | Error::SshJumpHostConnect { .. }
+                    | Error::SshConnectTimeout { .. }
+                    | Error::SshReconnect { .. }
+                    | Error::TunnelOpenTimeout { .. }
+            ),
+            Self::Timeout => matches!(
+                error,
+                Error::SshConnectTimeout { .. }
+                    | Error::TunnelOpenTimeout { .. }
+                    | Error::DbConnectTimeout { .. }
+                    | Error::QueryTimeout { .. }
+            ),
+            Self::Io => matches!(Self::mysql_error(error), Some(mysql_async::Error::Io(..))),
+            Self::ServerCode(code) => matches!(
+                Self::mysql_error(error),
+                Some(mysql_async::Error::Server(server_error)) if server_error.code == code
+            ),
+        }
+    }
+
+    /// Returns the `mysql_async` error from a connection or query failure.
+    fn mysql_error(error: &Error) -> Option<&mysql_async::Error> {
+        match error {
+            Error::MySqlConnectionRetrieve(error)
+            | Error::MySqlPrepare(error)
+            | Error::MySqlExecute(error)
+            | Error::QueryResultSetFetch(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        net::{IpAddr, Ipv4Addr},
+    };
+
+    use ssh_jumper::model::{Error as SshJumperError, HostAddress};
+
+    use super::RetryableError;
+    use crate::Error;
+
+    #[test]
+    fn ssh_matches_jump_host_connect() {
+        let error = Error::SshJumpHostConnect {
+            hop_number: 1,
+            jump_host_address: HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            error: Box::new(SshJumperError::SshTunnelOpenFail(io::Error::from(
+                io::ErrorKind::ConnectionRefused,
+            ))),
+        };
+
+        assert!(RetryableError::Ssh.matches(&error));
+        assert!(!RetryableError::Timeout.matches(&error));
+    }
+}
@@ -0,0 +1,57 @@
+use ssh_jumper::model::{HostAddress, HostSocketParams};
+
+use crate::{Error, QueryTarget, SshTunnelListener, SshTunnelMap};
+
+/// Prepared [`ConnectionRoute`] for a set of query targets.
+///
+/// This must be kept alive while connections to the query targets are needed,
+/// as SSH tunnels are closed when their session is dropped.
+///
+/// [`ConnectionRoute`]: crate::ConnectionRoute
+#[derive(Debug)]
+pub enum RouteMap<'qt> {
+    /// Query targets are connected to directly.
+    Direct,
+    /// Query targets are connected to through SSH tunnels.
+    SshTunnels(SshTunnelMap<'qt>),
+}
+
+impl<'qt> RouteMap<'qt> {
+    /// Returns the socket to connect to for the given query target.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SshTunnelNotFound`] if the query target has no SSH
+    /// tunnel.
+    pub fn db_socket<'query>(
+        &self,
+        query_target: &QueryTarget<'query>,
+    ) -> Result<HostSocketParams<'query>, Error> {
+        match self {
+            Self::Direct => Ok(query_target.db_socket()),
+            Self::SshTunnels(ssh_tunnel_map) => ssh_tunnel_map
+                .get(query_target.name.as_ref())
+                .map(|db_tunnel| HostSocketParams {
+                    address: HostAddress::IpAddr(db_tunnel.local_socket_addr.ip()),
+                    port: db_tunnel.local_socket_addr.port(),
+                })
+                .ok_or_else(|| Error::SshTunnelNotFound {
+                    jump_host_address: ssh_tunnel_map.jump_host_address.clone(),
+                    query_target: Box::new(query_target.clone().into_static()),
+                }),
+        }
+    }
+
+    /// Returns the error that occurred in the query target's tunnel, if any.
+    ///
+    /// This is the underlying cause when connecting to the query target fails
+    /// because the tunnel could not forward the connection.
+    pub fn tunnel_error_take(&self, query_target: &QueryTarget<'_>) -> Option<Error> {
+        match self {
+            Self::Direct => None,
+            Self::SshTunnels(ssh_tunnel_map) => ssh_tunnel_map
+                .get(query_target.name.as_ref())
+                .and_then(SshTunnelListener::take_error),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of a column in a query result row.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RowColumn {
+    /// Name or alias of the column's table in the query, empty if the column is
+    /// computed.
+    pub table: String,
+    /// Name or alias of the column.
+    pub name: String,
+}
+
+impl RowColumn {
+    /// Returns a new `RowColumn`.
+    pub fn new(table: String, name: String) -> Self {
+        Self { table, name }
+    }
+
+    /// Returns the column name qualified with its table, such as `a.id`.
+    ///
+    /// This is just the column name if there is no table.
+    pub fn qualified_name(&self) -> String {
+        if self.table.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.table, self.name)
+        }
+    }
+}
+
+impl From<&mysql_async::Column> for RowColumn {
+    fn from(column: &mysql_async::Column) -> Self {
+        Self {
+            table: column.table_str().into_owned(),
+            name: column.name_str().into_owned(),
+        }
+    }
+}
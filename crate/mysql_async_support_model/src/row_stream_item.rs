@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Column;
+
+/// Item yielded when streaming the rows of a query's result sets.
+///
+/// # Type Parameters
+///
+/// * `T`: Type that represents the values from each row. This may be either
+///   [`StringValues`] or [`TypedValues`].
+///
+/// [`StringValues`]: crate::StringValues
+/// [`TypedValues`]: crate::TypedValues
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum RowStreamItem<T> {
+    /// A row in the current result set.
+    Row(T),
+    /// End of the current result set.
+    ///
+    /// Rows after this belong to the next result set.
+    ResultSetEnd {
+        /// Number of rows affected by the statement.
+        affected_rows: u64,
+        /// Number of warnings.
+        warning_count: u16,
+        /// Message returned by the server.
+        info: String,
+        /// Descriptors of the columns in each row of the result set.
+        columns: Vec<Column>,
+    },
+}
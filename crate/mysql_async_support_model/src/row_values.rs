@@ -0,0 +1,376 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt,
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::Index,
+};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::RowColumn;
+
+/// Values of a query result row, with every column kept in order.
+///
+/// Unlike a map, this keeps columns that have the same name, such as the
+/// `id` columns in `SELECT a.id, b.id FROM a JOIN b`.
+///
+/// # Serialization
+///
+/// This is serialized as a map from column name to value. When a column name
+/// is not unique in the row, the table-qualified name is used, such as `a.id`.
+/// If that is still not unique, or is the same as another column's name, such
+/// as in ``SELECT a.id, b.id, 1 AS `a.id` ``, the column's index is appended,
+/// such as `a.id#0`.
+///
+/// When deserialized, each key becomes a column name without a table, as a
+/// key such as `a.id` may be either a table-qualified name or a column alias.
+/// This means [`get_by_qualified_name`] returns `None` for deserialized
+/// values, and [`get_by_name`] must be used with the serialized key instead.
+///
+/// [`get_by_name`]: Self::get_by_name
+/// [`get_by_qualified_name`]: Self::get_by_qualified_name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowValues<V> {
+    /// Names of the columns, in order.
+    columns: Vec<RowColumn>,
+    /// Values of the columns, in the same order as the columns.
+    values: Vec<V>,
+}
+
+impl<V> RowValues<V> {
+    /// Returns a new `RowValues` with no columns.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new `RowValues` with capacity for the given number of
+    /// columns.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            columns: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a column and its value.
+    pub fn push(&mut self, column: RowColumn, value: V) {
+        self.columns.push(column);
+        self.values.push(value);
+    }
+
+    /// Returns the number of columns.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether there are no columns.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the names of the columns, in order.
+    pub fn columns(&self) -> &[RowColumn] {
+        &self.columns
+    }
+
+    /// Returns the values of the columns, in order.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns the values of the columns, in order.
+    pub fn into_values(self) -> Vec<V> {
+        self.values
+    }
+
+    /// Returns the value at the given column index.
+    pub fn get(&self, index: usize) -> Option<&V> {
+        self.values.get(index)
+    }
+
+    /// Returns the value at the given column index mutably.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut V> {
+        self.values.get_mut(index)
+    }
+
+    /// Returns the value of the first column with the given name.
+    pub fn get_by_name(&self, name: &str) -> Option<&V> {
+        self.position(|column| column.name == name)
+            .map(|index| &self.values[index])
+    }
+
+    /// Returns the value of the first column with the given table and name.
+    ///
+    /// `table` is the table name or alias used in the query.
+    pub fn get_by_qualified_name(&self, table: &str, name: &str) -> Option<&V> {
+        self.position(|column| column.table == table && column.name == name)
+            .map(|index| &self.values[index])
+    }
+
+    /// Returns an iterator over each column and its value, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&RowColumn, &V)> + '_ {
+        self.columns.iter().zip(self.values.iter())
+    }
+
+    /// Returns the index of the first column that matches the predicate.
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: FnMut(&RowColumn) -> bool,
+    {
+        self.columns.iter().position(predicate)
+    }
+
+    /// Returns the key that each column is serialized with.
+    ///
+    /// Column names that are unique in the row are used as is. Other columns
+    /// get a generated key, which falls back to appending the column index
+    /// when it is the same as any other key.
+    fn serde_keys(&self) -> Vec<Cow<'_, str>> {
+        let mut name_counts = HashMap::<&str, usize>::new();
+        let mut qualified_name_counts = HashMap::<(&str, &str), usize>::new();
+        self.columns.iter().for_each(|column| {
+            *name_counts.entry(column.name.as_str()).or_default() += 1;
+            *qualified_name_counts
+                .entry((column.table.as_str(), column.name.as_str()))
+                .or_default() += 1;
+        });
+
+        let keys = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                if name_counts[column.name.as_str()] == 1 {
+                    Cow::Borrowed(column.name.as_str())
+                } else if !column.table.is_empty()
+                    && qualified_name_counts[&(column.table.as_str(), column.name.as_str())] == 1
+                {
+                    Cow::Owned(column.qualified_name())
+                } else {
+                    Cow::Owned(format!("{}#{}", column.qualified_name(), index))
+                }
+            })
+            .collect::<Vec<Cow<'_, str>>>();
+
+        // Generated keys may be the same as a column name, such as an alias
+        // of `a.id`, or as each other, so they are made unique by appending
+        // the column index until they are not taken.
+        let mut keys_taken = keys
+            .iter()
+            .filter_map(|key| match key {
+                Cow::Borrowed(name) => Some(name.to_string()),
+                Cow::Owned(_) => None,
+            })
+            .collect::<HashSet<String>>();
+        let mut key_counts = HashMap::<&str, usize>::new();
+        keys.iter()
+            .for_each(|key| *key_counts.entry(key.as_ref()).or_default() += 1);
+        let keys_colliding = key_counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(key, _)| key.to_string())
+            .collect::<HashSet<String>>();
+
+        keys.into_iter()
+            .enumerate()
+            .map(|(index, key)| match key {
+                Cow::Borrowed(_) => key,
+                Cow::Owned(mut key) => {
+                    if keys_colliding.contains(&key) || keys_taken.contains(&key) {
+                        key = format!("{}#{}", self.columns[index].qualified_name(), index);
+                        while keys_taken.contains(&key) {
+                            key = format!("{}#{}", key, index);
+                        }
+                    }
+                    keys_taken.insert(key.clone());
+                    Cow::Owned(key)
+                }
+            })
+            .collect()
+    }
+}
+
+impl<V> Default for RowValues<V> {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<V> Index<usize> for RowValues<V> {
+    type Output = V;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl<V> FromIterator<(RowColumn, V)> for RowValues<V> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (RowColumn, V)>,
+    {
+        let (columns, values) = iter.into_iter().unzip();
+        Self { columns, values }
+    }
+}
+
+impl<V> IntoIterator for RowValues<V> {
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<RowColumn>, std::vec::IntoIter<V>>;
+    type Item = (RowColumn, V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.columns.into_iter().zip(self.values)
+    }
+}
+
+impl<V> Serialize for RowValues<V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        self.serde_keys()
+            .iter()
+            .zip(self.values.iter())
+            .try_for_each(|(key, value)| map.serialize_entry(key, value))?;
+        map.end()
+    }
+}
+
+impl<'de, V> Deserialize<'de> for RowValues<V>
+where
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(RowValuesVisitor(PhantomData))
+    }
+}
+
+struct RowValuesVisitor<V>(PhantomData<V>);
+
+impl<'de, V> Visitor<'de> for RowValuesVisitor<V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = RowValues<V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of column names to values")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut row_values = RowValues::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((name, value)) = map.next_entry::<String, V>()? {
+            row_values.push(RowColumn::new(String::new(), name), value);
+        }
+        Ok(row_values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RowValues;
+    use crate::RowColumn;
+
+    fn row_values(columns: &[(&str, &str)]) -> RowValues<u32> {
+        columns
+            .iter()
+            .enumerate()
+            .map(|(index, (table, name))| {
+                (
+                    RowColumn::new(table.to_string(), name.to_string()),
+                    index as u32,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_names_are_kept() {
+        let row_values = row_values(&[("a", "id"), ("b", "id"), ("a", "name")]);
+
+        assert_eq!(3, row_values.len());
+        assert_eq!(Some(&0), row_values.get_by_name("id"));
+        assert_eq!(Some(&1), row_values.get_by_qualified_name("b", "id"));
+        assert_eq!(1, row_values[1]);
+    }
+
+    #[test]
+    fn serialize_unique_names_unqualified() {
+        let row_values = row_values(&[("a", "id"), ("a", "name")]);
+
+        assert_eq!(
+            r#"{"id":0,"name":1}"#,
+            serde_json::to_string(&row_values).expect("Failed to serialize `RowValues`.")
+        );
+    }
+
+    #[test]
+    fn serialize_duplicate_names_qualified() {
+        let row_values = row_values(&[("a", "id"), ("b", "id"), ("", "n"), ("", "n")]);
+
+        assert_eq!(
+            r#"{"a.id":0,"b.id":1,"n#2":2,"n#3":3}"#,
+            serde_json::to_string(&row_values).expect("Failed to serialize `RowValues`.")
+        );
+    }
+
+    #[test]
+    fn serialize_qualified_name_same_as_alias_uses_index() {
+        let row_values = row_values(&[("a", "id"), ("b", "id"), ("", "a.id")]);
+
+        assert_eq!(
+            r#"{"a.id#0":0,"b.id":1,"a.id":2}"#,
+            serde_json::to_string(&row_values).expect("Failed to serialize `RowValues`.")
+        );
+    }
+
+    #[test]
+    fn serialize_indexed_name_same_as_alias_is_unique() {
+        let row_values = row_values(&[("", "n"), ("", "n"), ("", "n#1")]);
+
+        assert_eq!(
+            r#"{"n#0":0,"n#1#1":1,"n#1":2}"#,
+            serde_json::to_string(&row_values).expect("Failed to serialize `RowValues`.")
+        );
+    }
+
+    #[test]
+    fn deserialize_drops_table() {
+        let row_values = row_values(&[("a", "id"), ("b", "id")]);
+        let serialized =
+            serde_json::to_string(&row_values).expect("Failed to serialize `RowValues`.");
+        let deserialized = serde_json::from_str::<RowValues<u32>>(&serialized)
+            .expect("Failed to deserialize `RowValues`.");
+
+        assert_eq!(None, deserialized.get_by_qualified_name("a", "id"));
+        assert_eq!(Some(&0), deserialized.get_by_name("a.id"));
+        assert_eq!(Some(&1), deserialized.get_by_name("b.id"));
+    }
+
+    #[test]
+    fn deserialize_keeps_order() {
+        let deserialized = serde_json::from_str::<RowValues<u32>>(r#"{"b":0,"a":1}"#)
+            .expect("Failed to deserialize `RowValues`.");
+
+        assert_eq!(row_values(&[("", "b"), ("", "a")]), deserialized);
+    }
+}
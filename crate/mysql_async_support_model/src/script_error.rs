@@ -0,0 +1,21 @@
+use crate::Error;
+
+/// Query target name and error when attempting to run a SQL script.
+#[derive(Debug)]
+pub struct ScriptError {
+    /// Name of the query target.
+    pub name: String,
+    /// Line of the statement that failed, `None` if the error occurred before
+    /// any statement was run.
+    pub line: Option<usize>,
+    /// The error that occurred when running the script.
+    pub error: Error,
+}
+
+impl std::ops::Deref for ScriptError {
+    type Target = Error;
+
+    fn deref(&self) -> &Self::Target {
+        &self.error
+    }
+}
@@ -0,0 +1,9 @@
+/// What to do when a statement in a SQL script fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScriptErrorPolicy {
+    /// Do not run the remaining statements for the query target.
+    #[default]
+    StopOnError,
+    /// Continue running the remaining statements for the query target.
+    Continue,
+}
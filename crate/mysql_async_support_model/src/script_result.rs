@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use crate::StatementResult;
+
+/// Query target name and the results of each statement in a SQL script.
+///
+/// Statements that failed are reported as [`ScriptError`]s instead.
+///
+/// [`ScriptError`]: crate::ScriptError
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ScriptResult<T> {
+    /// Name of the query target.
+    pub name: String,
+    /// Results of the statements that succeeded, in script order.
+    pub statement_results: Vec<StatementResult<T>>,
+}
@@ -0,0 +1,268 @@
+use crate::SqlStatement;
+
+/// Statements parsed from a SQL script.
+///
+/// Scripts are split in the same way as the `mysql` command line client:
+///
+/// * Statements end with the current delimiter, which is `;` by default.
+/// * `DELIMITER <delimiter>` at the start of a statement changes the delimiter,
+///   so that procedure bodies may contain `;`.
+/// * `--`, `#`, and `/* */` comments are removed. `/*! */` and `/*+ */`
+///   comments are kept, as the server interprets them.
+/// * Delimiters inside quoted strings, identifiers, and comments are not split
+///   on.
+///
+/// Comments are detected before delimiters, so a delimiter that starts a
+/// comment, such as `#` or `--`, is read as a comment, and must not be used.
+///
+/// Backslashes escape quotes in strings, unless the script is parsed with
+/// [`SqlScript::parse_with_backslash_escapes`] for servers that use the
+/// [`NO_BACKSLASH_ESCAPES`] SQL mode.
+///
+/// [`NO_BACKSLASH_ESCAPES`]: https://dev.mysql.com/doc/refman/8.0/en/sql-mode.html#sqlmode_no_backslash_escapes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SqlScript {
+    /// Statements in the script, in order.
+    pub statements: Vec<SqlStatement>,
+}
+
+impl SqlScript {
+    /// Delimiter that statements end with, until changed by `DELIMITER`.
+    pub const DELIMITER_DEFAULT: &'static str = ";";
+
+    /// Parses a SQL script into statements, where backslashes escape quotes in
+    /// strings.
+    pub fn parse(script: &str) -> Self {
+        Self::parse_with_backslash_escapes(script, true)
+    }
+
+    /// Parses a SQL script into statements.
+    ///
+    /// # Parameters
+    ///
+    /// * `script`: The SQL script to parse.
+    /// * `backslash_escapes`: Whether backslashes escape quotes in strings.
+    ///   This must be `false` when the server's `sql_mode` includes
+    ///   `NO_BACKSLASH_ESCAPES`, where `'a\'` is a complete string.
+    pub fn parse_with_backslash_escapes(script: &str, backslash_escapes: bool) -> Self {
+        let mut statements = Vec::new();
+        let mut delimiter = String::from(Self::DELIMITER_DEFAULT);
+        let mut sql_text = String::new();
+        let mut sql_text_line = None;
+        let mut line = 1;
+        let mut rest = script;
+
+        while let Some(c) = rest.chars().next() {
+            // Like the `mysql` client, `DELIMITER` is only recognized at the start of a
+            // statement.
+            if sql_text_line.is_none() {
+                if let Some((delimiter_new, len)) = Self::delimiter_command(rest) {
+                    delimiter = delimiter_new.to_string();
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+
+            // Comments are checked first, so that a delimiter such as `/` is not
+            // split on when it starts a comment.
+            let len = if Self::is_line_comment(rest) {
+                // The newline is kept, so that it is counted.
+                rest.find('\n').unwrap_or(rest.len())
+            } else if rest.starts_with("/*") && !Self::is_server_comment(rest) {
+                let len = rest[2..]
+                    .find("*/")
+                    .map(|index| index + 4)
+                    .unwrap_or(rest.len());
+                line += rest[..len].matches('\n').count();
+
+                // The comment separates tokens on either side of it.
+                if sql_text_line.is_some() {
+                    sql_text.push(' ');
+                }
+                len
+            } else if rest.starts_with(delimiter.as_str()) && !Self::is_server_comment(rest) {
+                if let Some(line) = sql_text_line.take() {
+                    statements.push(SqlStatement {
+                        line,
+                        sql_text: sql_text.trim_end().to_string(),
+                    });
+                }
+                sql_text.clear();
+                delimiter.len()
+            } else {
+                let len = match c {
+                    '\'' | '"' | '`' => Self::quoted_len(rest, c, backslash_escapes),
+                    '/' if Self::is_server_comment(rest) => rest[2..]
+                        .find("*/")
+                        .map(|index| index + 4)
+                        .unwrap_or(rest.len()),
+                    _ => c.len_utf8(),
+                };
+                let token = &rest[..len];
+                if sql_text_line.is_none() && !token.trim().is_empty() {
+                    sql_text_line = Some(line);
+                }
+                if sql_text_line.is_some() {
+                    sql_text.push_str(token);
+                }
+                line += token.matches('\n').count();
+                len
+            };
+            rest = &rest[len..];
+        }
+
+        if let Some(line) = sql_text_line {
+            statements.push(SqlStatement {
+                line,
+                sql_text: sql_text.trim_end().to_string(),
+            });
+        }
+
+        Self { statements }
+    }
+
+    /// Returns the new delimiter and the length of the command, if the line
+    /// is a `DELIMITER` command.
+    fn delimiter_command(rest: &str) -> Option<(&str, usize)> {
+        let command = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        let mut tokens = command.split_whitespace();
+        match (tokens.next(), tokens.next()) {
+            (Some(keyword), Some(delimiter)) if keyword.eq_ignore_ascii_case("DELIMITER") => {
+                Some((delimiter, command.len()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether the text starts with a comment that runs until the end
+    /// of the line.
+    fn is_line_comment(rest: &str) -> bool {
+        rest.starts_with('#')
+            || rest
+                .strip_prefix("--")
+                .map(|after| after.is_empty() || after.starts_with(char::is_whitespace))
+                .unwrap_or(false)
+    }
+
+    /// Returns whether the text starts with a comment that the server
+    /// interprets, such as version-specific code or optimizer hints.
+    fn is_server_comment(rest: &str) -> bool {
+        rest.starts_with("/*!") || rest.starts_with("/*+")
+    }
+
+    /// Returns the length of the quoted string or identifier at the start of
+    /// the text, including the quotes.
+    fn quoted_len(rest: &str, quote: char, backslash_escapes: bool) -> usize {
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((index, c)) = chars.next() {
+            if c == '\\' && quote != '`' && backslash_escapes {
+                chars.next();
+            } else if c == quote {
+                return index + c.len_utf8();
+            }
+        }
+
+        rest.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqlScript;
+    use crate::SqlStatement;
+
+    fn statement(line: usize, sql_text: &str) -> SqlStatement {
+        SqlStatement {
+            line,
+            sql_text: String::from(sql_text),
+        }
+    }
+
+    #[test]
+    fn parse_splits_statements_and_removes_comments() {
+        let script = "\
+-- Select things.
+SELECT 1;
+# Another comment.
+SELECT /* inline */ 2
+  FROM dual; SELECT 3
+";
+
+        assert_eq!(
+            vec![
+                statement(2, "SELECT 1"),
+                statement(4, "SELECT   2\n  FROM dual"),
+                statement(5, "SELECT 3"),
+            ],
+            SqlScript::parse(script).statements
+        );
+    }
+
+    #[test]
+    fn parse_uses_delimiter_command() {
+        let script = "\
+DELIMITER //
+CREATE PROCEDURE p()
+BEGIN
+  SELECT 1;
+END//
+DELIMITER ;
+CALL p();
+";
+
+        assert_eq!(
+            vec![
+                statement(2, "CREATE PROCEDURE p()\nBEGIN\n  SELECT 1;\nEND"),
+                statement(7, "CALL p()"),
+            ],
+            SqlScript::parse(script).statements
+        );
+    }
+
+    #[test]
+    fn parse_does_not_split_on_delimiter_in_comment() {
+        let script = "\
+DELIMITER /
+SELECT /* comment */ 1/
+SELECT 2 -- /
+/
+/*!40101 SET x = 1 */ /
+";
+
+        assert_eq!(
+            vec![
+                statement(2, "SELECT   1"),
+                statement(3, "SELECT 2"),
+                statement(5, "/*!40101 SET x = 1 */"),
+            ],
+            SqlScript::parse(script).statements
+        );
+    }
+
+    #[test]
+    fn parse_without_backslash_escapes() {
+        let script = r"SELECT 'a\'; SELECT 2;";
+
+        assert_eq!(
+            vec![statement(1, r"SELECT 'a\'"), statement(1, "SELECT 2")],
+            SqlScript::parse_with_backslash_escapes(script, false).statements
+        );
+        assert_eq!(
+            vec![statement(1, r"SELECT 'a\'; SELECT 2;")],
+            SqlScript::parse(script).statements
+        );
+    }
+
+    #[test]
+    fn parse_does_not_split_on_quoted_delimiters() {
+        let script = r#"SELECT 'a;b', "c\";d", `e;f`; /*!40101 SET x = 1 */;"#;
+
+        assert_eq!(
+            vec![
+                statement(1, r#"SELECT 'a;b', "c\";d", `e;f`"#),
+                statement(1, "/*!40101 SET x = 1 */"),
+            ],
+            SqlScript::parse(script).statements
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// A statement from a SQL script.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SqlStatement {
+    /// Line in the script that the statement starts on, starting from `1`.
+    pub line: usize,
+    /// SQL text of the statement, without the delimiter.
+    pub sql_text: String,
+}
+
+impl SqlStatement {
+    /// Returns whether the statement commits or rolls back the current
+    /// transaction, either explicitly or implicitly.
+    ///
+    /// This includes DDL other than `CREATE` or `DROP` of temporary tables,
+    /// account management, transaction control such as `BEGIN` and `COMMIT`,
+    /// `LOCK TABLES`, `SET autocommit`, and administrative statements such as
+    /// `ANALYZE TABLE` or `FLUSH`. Statements that are run by a stored
+    /// procedure through `CALL` are not detected.
+    ///
+    /// See <https://dev.mysql.com/doc/refman/8.0/en/implicit-commit.html>.
+    pub fn ends_transaction(&self) -> bool {
+        Self::sql_text_ends_transaction(&self.sql_text)
+    }
+
+    /// Returns whether the SQL text of a single statement commits or rolls
+    /// back the current transaction.
+    fn sql_text_ends_transaction(sql_text: &str) -> bool {
+        // Version comments such as `/*!40101 SET ... */` are run by the server.
+        let sql_text = sql_text.trim_start();
+        let sql_text = match sql_text.strip_prefix("/*!") {
+            Some(after) => after.trim_start_matches(|c: char| c.is_ascii_digit()),
+            None => sql_text,
+        };
+        let sql_text = sql_text.to_ascii_uppercase();
+        let mut words = sql_text
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '@'))
+            .filter(|word| !word.is_empty());
+        let first = words.next().unwrap_or_default();
+        let second = words.next().unwrap_or_default();
+        let third = words.next().unwrap_or_default();
+
+        match first {
+            "CREATE" | "DROP" => second != "TEMPORARY",
+            "ALTER" | "RENAME" | "TRUNCATE" | "GRANT" | "REVOKE" | "INSTALL" | "UNINSTALL"
+            | "IMPORT" => true,
+            "BEGIN" | "COMMIT" | "START" | "STOP" | "XA" | "LOCK" | "UNLOCK" => true,
+            // `ROLLBACK TO SAVEPOINT` keeps the transaction open.
+            "ROLLBACK" => second != "TO" && !(second == "WORK" && third == "TO"),
+            "ANALYZE" | "CACHE" | "CHECK" | "OPTIMIZE" | "REPAIR" | "FLUSH" | "RESET"
+            | "CHANGE" => true,
+            "LOAD" => second == "INDEX",
+            "SET" => second == "PASSWORD" || sql_text.contains("AUTOCOMMIT"),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqlStatement;
+
+    fn ends_transaction(sql_text: &str) -> bool {
+        SqlStatement {
+            line: 1,
+            sql_text: String::from(sql_text),
+        }
+        .ends_transaction()
+    }
+
+    #[test]
+    fn ends_transaction_for_ddl_and_transaction_control() {
+        assert!(ends_transaction("CREATE TABLE t (id INT)"));
+        assert!(ends_transaction("  alter table t add column c int"));
+        assert!(ends_transaction("DROP INDEX i ON t"));
+        assert!(ends_transaction("TRUNCATE t"));
+        assert!(ends_transaction("COMMIT"));
+        assert!(ends_transaction("ROLLBACK"));
+        assert!(ends_transaction("BEGIN"));
+        assert!(ends_transaction("START TRANSACTION"));
+        assert!(ends_transaction("LOCK TABLES t WRITE"));
+        assert!(ends_transaction("SET autocommit = 1"));
+        assert!(ends_transaction("SET @@session.autocommit = 1"));
+        assert!(ends_transaction("GRANT SELECT ON db.* TO u"));
+        assert!(ends_transaction("ANALYZE TABLE t"));
+        assert!(ends_transaction("/*!40101 CREATE TABLE t (id INT) */"));
+    }
+
+    #[test]
+    fn does_not_end_transaction_for_dml_and_temporary_tables() {
+        assert!(!ends_transaction("SELECT 1"));
+        assert!(!ends_transaction("UPDATE t SET c = 1"));
+        assert!(!ends_transaction("INSERT INTO t VALUES (1)"));
+        assert!(!ends_transaction("SET @a = 1"));
+        assert!(!ends_transaction("CREATE TEMPORARY TABLE t (id INT)"));
+        assert!(!ends_transaction("DROP TEMPORARY TABLE t"));
+        assert!(!ends_transaction("ROLLBACK TO SAVEPOINT s"));
+        assert!(!ends_transaction("ROLLBACK WORK TO s"));
+        assert!(!ends_transaction("SAVEPOINT s"));
+        assert!(!ends_transaction("LOAD DATA INFILE 'f' INTO TABLE t"));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{fmt, net::TcpStream};
+
+use async_ssh2_lite::AsyncSession;
+
+/// SSH sessions to a chain of jump hosts, each opened through the previous.
+///
+/// Sessions are dropped in reverse order, so nested sessions are closed before
+/// the sessions they are tunnelled through.
+pub struct SshSessionChain {
+    /// Sessions to the second and subsequent jump hosts.
+    ///
+    /// This is declared first so that it is dropped before `ssh_session`.
+    pub ssh_sessions_nested: Vec<AsyncSession<TcpStream>>,
+    /// Session to the first jump host.
+    pub ssh_session: AsyncSession<TcpStream>,
+}
+
+impl SshSessionChain {
+    /// Returns the session to the last jump host in the chain.
+    ///
+    /// Tunnels to the database servers are opened through this session.
+    pub fn last(&self) -> &AsyncSession<TcpStream> {
+        self.ssh_sessions_nested.last().unwrap_or(&self.ssh_session)
+    }
+
+    /// Returns the number of jump hosts in the chain.
+    pub fn hop_count(&self) -> usize {
+        self.ssh_sessions_nested.len() + 1
+    }
+}
+
+impl fmt::Debug for SshSessionChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // AsyncSession which backs `SshSession` is `!Debug`.
+        // https://docs.rs/async-ssh2-lite/latest/async_ssh2_lite/struct.AsyncSession.html
+        f.debug_struct("SshSessionChain")
+            .field("hop_count", &self.hop_count())
+            .finish()
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use tokio::task::JoinHandle;
+
+use crate::Error;
+
+/// SSH tunnel that forwards each local connection through a new channel.
+///
+/// The tunnel is closed when this is dropped. The SSH sessions are closed when
+/// all tunnels that use them are dropped.
+#[derive(Debug)]
+pub struct SshTunnelListener {
+    /// Local address that forwards to the target host.
+    pub local_socket_addr: SocketAddr,
+    /// Task that accepts local connections.
+    join_handle: JoinHandle<()>,
+    /// Last error that occurred when opening a channel for a local connection.
+    error: Arc<Mutex<Option<Error>>>,
+}
+
+impl SshTunnelListener {
+    /// Returns a new `SshTunnelListener`.
+    ///
+    /// # Parameters
+    ///
+    /// * `local_socket_addr`: Local address that forwards to the target host.
+    /// * `join_handle`: Task that accepts local connections, which is aborted
+    ///   when this is dropped.
+    /// * `error`: Slot that the task records channel errors in.
+    pub fn new(
+        local_socket_addr: SocketAddr,
+        join_handle: JoinHandle<()>,
+        error: Arc<Mutex<Option<Error>>>,
+    ) -> Self {
+        Self {
+            local_socket_addr,
+            join_handle,
+            error,
+        }
+    }
+
+    /// Returns the last error that occurred when opening a channel, if any.
+    ///
+    /// When a channel cannot be opened, the local connection is closed, so the
+    /// database client only sees a connection error. This returns the
+    /// underlying cause.
+    pub fn take_error(&self) -> Option<Error> {
+        self.error
+            .lock()
+            .expect("`SshTunnelListener` error lock poisoned.")
+            .take()
+    }
+}
+
+impl Drop for SshTunnelListener {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
@@ -1,25 +1,26 @@
 use std::{
     collections::HashMap,
-    fmt,
-    net::SocketAddr,
     ops::{Deref, DerefMut},
 };
 
-use ssh_jumper::SshSession;
+use ssh_jumper::model::HostAddress;
 
-/// Keeps SshSession alive while tunnels are needed.
+use crate::SshTunnelListener;
+
+/// Keeps SSH tunnels alive while they are needed.
 ///
-/// This is needed to keep the SSH session alive, because the lifetime of the
-/// [`SocketAddr`] is linked to the [`SshSession`].
+/// Each [`SshTunnelListener`] keeps the SSH sessions it uses alive, so the
+/// sessions are closed when this map is dropped.
+#[derive(Debug)]
 pub struct SshTunnelMap<'qt> {
-    /// The SSH session the tunnels are created from.
-    pub ssh_session: SshSession,
-    /// Mapping between query target name and socket address.
-    pub qt_name_to_tunnel: HashMap<&'qt str, SocketAddr>,
+    /// Address of the last jump host, which the tunnels are created from.
+    pub jump_host_address: HostAddress<'static>,
+    /// Mapping between query target name and tunnel.
+    pub qt_name_to_tunnel: HashMap<&'qt str, SshTunnelListener>,
 }
 
 impl<'qt> Deref for SshTunnelMap<'qt> {
-    type Target = HashMap<&'qt str, SocketAddr>;
+    type Target = HashMap<&'qt str, SshTunnelListener>;
 
     fn deref(&self) -> &Self::Target {
         &self.qt_name_to_tunnel
@@ -31,13 +32,3 @@ impl<'qt> DerefMut for SshTunnelMap<'qt> {
         &mut self.qt_name_to_tunnel
     }
 }
-
-impl<'qt> fmt::Debug for SshTunnelMap<'qt> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // AsyncSession which backs `SshSession` is `!Debug`.
-        // https://docs.rs/async-ssh2-lite/latest/async_ssh2_lite/struct.AsyncSession.html
-        f.debug_struct("SshTunnelMap")
-            .field("qt_name_to_tunnel", &self.qt_name_to_tunnel)
-            .finish()
-    }
-}
@@ -0,0 +1,22 @@
+/// Whether and how to secure connections to the database server.
+///
+/// These follow the MySQL client's `--ssl-mode` values, except `PREFERRED`,
+/// as connections do not fall back to plain text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SslMode {
+    /// Connect without TLS.
+    #[default]
+    Disabled,
+    /// Connect with TLS, without verifying the server certificate.
+    Required,
+    /// Connect with TLS, and verify the server certificate against the CA.
+    ///
+    /// The server's host name is not verified, which allows connecting through
+    /// SSH tunnels, where the host name is the local tunnel address.
+    VerifyCa,
+    /// Connect with TLS, and verify the server certificate and host name.
+    ///
+    /// This cannot be used through SSH tunnels, as the host name is the local
+    /// tunnel address.
+    VerifyIdentity,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ResultSet;
+
+/// Result sets returned by a statement in a SQL script.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct StatementResult<T> {
+    /// Line in the script that the statement starts on, starting from `1`.
+    pub line: usize,
+    /// Result sets returned by the statement.
+    pub result_sets: Vec<ResultSet<T>>,
+}
@@ -1,62 +1,90 @@
-use std::{
-    borrow::Cow,
-    ops::{Deref, DerefMut},
-};
+use std::ops::{Deref, DerefMut};
 
-use indexmap::IndexMap;
-use mysql_async::{prelude::FromRow, Column, FromRowError, Row};
+use mysql_async::{prelude::FromRow, FromRowError, Row, Value as MySqlValue};
 use serde::{Deserialize, Serialize};
 
+use crate::{value::DateParts, ColumnType, RowColumn, RowValues};
+
 /// Represents a query result row, with all values stringified.
+///
+/// Each value is the text that MySQL would display for it, without quotes or
+/// escapes, and `NULL` is `None`, which is serialized as `null`. Binary values
+/// that are not valid UTF-8 are converted lossily.
+///
+/// Every column is kept, even if multiple columns have the same name.
+///
+/// # Breaking Change
+///
+/// This wraps [`RowValues`] instead of an `IndexMap<String, Option<String>>`,
+/// so map methods such as `get(name)` and `keys()` are replaced by
+/// [`RowValues::get_by_name`] and [`RowValues::columns`]. The serialized form
+/// is still a map from column name to value, but columns with the same name
+/// get distinct keys, where previously the last column replaced the others.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
-pub struct StringValues(pub IndexMap<String, String>);
+pub struct StringValues(pub RowValues<Option<String>>);
 
 impl StringValues {
-    /// Whether to use backslashes to escape values.
-    ///
-    /// This is written in the negative form to match `mysql_async`'s
-    /// [parameter].
-    ///
-    /// [parameter]: https://docs.rs/mysql_async/0.27.1/mysql_async/enum.Value.html#method.as_sql
-    const NO_ESCAPE_BACKSLASH: bool = true;
+    /// Returns the text of a value from a column with the given type, or
+    /// `None` if the value is `NULL`.
+    fn value_text(value: MySqlValue, column_type: ColumnType) -> Option<String> {
+        let text = match value {
+            MySqlValue::NULL => return None,
+            MySqlValue::Bytes(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+            },
+            MySqlValue::Int(v) => v.to_string(),
+            MySqlValue::UInt(v) => v.to_string(),
+            MySqlValue::Float(v) => v.to_string(),
+            MySqlValue::Double(v) => v.to_string(),
+            MySqlValue::Date(year, month, day, hour, minutes, seconds, micro_seconds) => {
+                let date_parts = DateParts {
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minutes,
+                    seconds,
+                    micro_seconds,
+                };
+                date_parts.text(column_type)
+            }
+            MySqlValue::Time(is_negative, days, hours, minutes, seconds, micro_seconds) => {
+                let sign = if is_negative { "-" } else { "" };
+                let hours = u64::from(days) * 24 + u64::from(hours);
+                let mut text = format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds);
+                if micro_seconds != 0 {
+                    text.push_str(&format!(".{:06}", micro_seconds));
+                }
+                text
+            }
+        };
+
+        Some(text)
+    }
 }
 
 impl FromRow for StringValues {
-    // `column_names_lossy` appears to be a needless_collect to clippy, but it is
-    // needed as `row.unwrap()` consumes `Row`.
-    #[allow(clippy::needless_collect)]
     fn from_row_opt(row: Row) -> Result<Self, FromRowError> {
-        let column_names_lossy = row
-            .columns_ref()
-            .iter()
-            .map(Column::name_str)
-            .map(Cow::into_owned)
-            .collect::<Vec<String>>();
+        // `row.unwrap()` consumes `Row`, so the columns are held separately.
+        let columns = row.columns();
 
         // unwrap here is not `Result::unwrap`, but `Row::unwrap`
-        let values = row
-            .unwrap()
-            .into_iter()
-            .map(|value| value.as_sql(Self::NO_ESCAPE_BACKSLASH))
-            .map(|value| {
-                // Remove surrounding `'` characters
-                value
-                    .strip_prefix('\'')
-                    .and_then(|value| value.strip_suffix('\''))
-                    .map(str::to_string)
-                    .unwrap_or(value)
-            });
-        let values = column_names_lossy
-            .into_iter()
-            .zip(values)
-            .collect::<IndexMap<String, String>>();
+        let values = columns
+            .iter()
+            .zip(row.unwrap())
+            .map(|(column, value)| {
+                let value = Self::value_text(value, ColumnType::from(column.column_type()));
+                (RowColumn::from(column), value)
+            })
+            .collect::<RowValues<Option<String>>>();
 
         Ok(StringValues(values))
     }
 }
 
 impl Deref for StringValues {
-    type Target = IndexMap<String, String>;
+    type Target = RowValues<Option<String>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -68,3 +96,73 @@ impl DerefMut for StringValues {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mysql_async::Value as MySqlValue;
+
+    use super::StringValues;
+    use crate::{ColumnType, RowColumn};
+
+    #[test]
+    fn value_text_null_is_distinct_from_string_null() {
+        assert_eq!(
+            None,
+            StringValues::value_text(MySqlValue::NULL, ColumnType::VarString)
+        );
+        assert_eq!(
+            Some(String::from("NULL")),
+            StringValues::value_text(MySqlValue::Bytes(b"NULL".to_vec()), ColumnType::VarString)
+        );
+    }
+
+    #[test]
+    fn value_text_is_unescaped() {
+        assert_eq!(
+            Some(String::from(r#"it's a "quote" \ backslash"#)),
+            StringValues::value_text(
+                MySqlValue::Bytes(br#"it's a "quote" \ backslash"#.to_vec()),
+                ColumnType::VarString
+            )
+        );
+    }
+
+    #[test]
+    fn value_text_temporal() {
+        assert_eq!(
+            Some(String::from("2021-05-30")),
+            StringValues::value_text(MySqlValue::Date(2021, 5, 30, 0, 0, 0, 0), ColumnType::Date)
+        );
+        assert_eq!(
+            Some(String::from("2021-05-30 12:06:53.000445")),
+            StringValues::value_text(
+                MySqlValue::Date(2021, 5, 30, 12, 6, 53, 445),
+                ColumnType::DateTime
+            )
+        );
+        assert_eq!(
+            Some(String::from("-26:03:04")),
+            StringValues::value_text(MySqlValue::Time(true, 1, 2, 3, 4, 0), ColumnType::Time)
+        );
+    }
+
+    #[test]
+    fn serialize_null_as_json_null() {
+        let string_values = StringValues(
+            vec![
+                (RowColumn::new(String::new(), String::from("a")), None),
+                (
+                    RowColumn::new(String::new(), String::from("b")),
+                    Some(String::from("NULL")),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            r#"{"a":null,"b":"NULL"}"#,
+            serde_json::to_string(&string_values).expect("Failed to serialize `StringValues`.")
+        );
+    }
+}
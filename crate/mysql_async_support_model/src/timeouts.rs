@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+/// Maximum durations for each phase of running queries on a query target.
+///
+/// A phase with a `None` timeout is not time-bounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Maximum duration to open the SSH sessions through all jump hosts.
+    pub ssh_connect: Option<Duration>,
+    /// Maximum duration to open a tunnel channel to the database server.
+    pub tunnel_open: Option<Duration>,
+    /// Maximum duration to get a connection to the database server.
+    pub db_connect: Option<Duration>,
+    /// Maximum duration to execute a query and fetch its results.
+    pub query: Option<Duration>,
+    /// Maximum duration for all phases of a query target, from when its SSH
+    /// session is opened until its results are fetched.
+    pub target: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Default maximum duration to get a database connection.
+    pub const DB_CONNECT_DEFAULT: Duration = Duration::from_secs(30);
+    /// Default maximum duration to open the SSH sessions.
+    pub const SSH_CONNECT_DEFAULT: Duration = Duration::from_secs(30);
+    /// Default maximum duration to open a tunnel channel.
+    pub const TUNNEL_OPEN_DEFAULT: Duration = Duration::from_secs(30);
+
+    /// Returns `Timeouts` where no phase is time-bounded.
+    pub fn none() -> Self {
+        Self {
+            ssh_connect: None,
+            tunnel_open: None,
+            db_connect: None,
+            query: None,
+            target: None,
+        }
+    }
+
+    /// Sets the maximum duration to open the SSH sessions.
+    pub fn with_ssh_connect(mut self, ssh_connect: Option<Duration>) -> Self {
+        self.ssh_connect = ssh_connect;
+        self
+    }
+
+    /// Sets the maximum duration to open a tunnel channel.
+    pub fn with_tunnel_open(mut self, tunnel_open: Option<Duration>) -> Self {
+        self.tunnel_open = tunnel_open;
+        self
+    }
+
+    /// Sets the maximum duration to get a database connection.
+    pub fn with_db_connect(mut self, db_connect: Option<Duration>) -> Self {
+        self.db_connect = db_connect;
+        self
+    }
+
+    /// Sets the maximum duration to execute a query and fetch its results.
+    pub fn with_query(mut self, query: Option<Duration>) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Sets the maximum duration for all phases of a query target.
+    pub fn with_target(mut self, target: Option<Duration>) -> Self {
+        self.target = target;
+        self
+    }
+}
+
+impl Default for Timeouts {
+    /// Returns `Timeouts` where connecting is time-bounded, but queries are
+    /// not.
+    fn default() -> Self {
+        Self {
+            ssh_connect: Some(Self::SSH_CONNECT_DEFAULT),
+            tunnel_open: Some(Self::TUNNEL_OPEN_DEFAULT),
+            db_connect: Some(Self::DB_CONNECT_DEFAULT),
+            query: None,
+            target: None,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/// How each query target's transaction is run in a coordinated run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransactionMode {
+    /// `START TRANSACTION`, then `COMMIT` or `ROLLBACK`.
+    ///
+    /// Transactions are committed only after every query target succeeded, but
+    /// a commit that fails on one target cannot undo the commits on others.
+    #[default]
+    Local,
+    /// XA transactions, which are prepared on every query target before any
+    /// are committed.
+    ///
+    /// A prepared transaction survives server restarts, so a commit is very
+    /// unlikely to fail after every target has prepared. If it does, the
+    /// transaction stays prepared and holds its locks until it is resolved,
+    /// see [`TransactionOutcome::CommitFailed`].
+    ///
+    /// [`TransactionOutcome::CommitFailed`]: crate::TransactionOutcome::CommitFailed
+    Xa,
+}
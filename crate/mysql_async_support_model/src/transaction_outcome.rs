@@ -0,0 +1,36 @@
+use crate::Error;
+
+/// Final state of a query target's transaction in a coordinated run.
+#[derive(Debug)]
+pub enum TransactionOutcome {
+    /// The transaction was committed.
+    Committed,
+    /// The transaction was rolled back, or never started, so nothing was
+    /// committed on the query target.
+    RolledBack,
+    /// Every query target succeeded, but committing on this target failed.
+    ///
+    /// For XA transactions, the transaction is left prepared on the server if
+    /// `XA COMMIT` failed or timed out, and holds its locks until it is
+    /// resolved manually with `XA COMMIT <xid>` or `XA ROLLBACK <xid>`. Other
+    /// query targets may already be committed, so the transaction should
+    /// usually be committed. `XA RECOVER` lists prepared transactions.
+    CommitFailed {
+        /// XA transaction ID in the form used by `XA COMMIT`, such as
+        /// `'gtrid','0.1'`, or `None` for local transactions.
+        xid: Option<String>,
+        /// The error that occurred when committing.
+        error: Error,
+    },
+    /// Rolling back the transaction failed.
+    ///
+    /// For XA transactions, the transaction may still be prepared on the
+    /// server, and must be resolved manually with `XA ROLLBACK <xid>`.
+    RollbackFailed {
+        /// XA transaction ID in the form used by `XA ROLLBACK`, such as
+        /// `'gtrid','0.1'`, or `None` for local transactions.
+        xid: Option<String>,
+        /// The error that occurred when rolling back.
+        error: Error,
+    },
+}
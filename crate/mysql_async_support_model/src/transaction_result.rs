@@ -0,0 +1,18 @@
+use crate::{QueryTarget, TransactionOutcome};
+
+/// Result of a query target's work, and what happened to its transaction.
+///
+/// # Type Parameters
+///
+/// * `T`: Type returned by the work on the query target.
+/// * `E`: Error returned by the work on the query target.
+#[derive(Debug)]
+pub struct TransactionResult<'qt, T, E> {
+    /// The query target the work was run on.
+    pub query_target: &'qt QueryTarget<'qt>,
+    /// Result of the work, before the transaction was committed or rolled
+    /// back.
+    pub result: Result<T, E>,
+    /// Whether the transaction was committed or rolled back.
+    pub outcome: TransactionOutcome,
+}
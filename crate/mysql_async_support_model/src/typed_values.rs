@@ -1,59 +1,53 @@
-use std::{
-    borrow::Cow,
-    ops::{Deref, DerefMut},
-};
+use std::ops::{Deref, DerefMut};
 
-use indexmap::IndexMap;
-use mysql_async::{prelude::FromRow, Column, FromRowError, Row};
+use mysql_async::{prelude::FromRow, FromRowError, Row};
 use serde::{Deserialize, Serialize};
 
-use crate::Value;
+use crate::{ColumnFlags, ColumnType, RowColumn, RowValues, Value};
 
-/// Represents a query result row, with all values stringified.
+/// Represents a query result row, with values decoded according to their
+/// column types.
 ///
-/// # Note
+/// Values are decoded with [`Value::from_column_value`], so the text and binary
+/// protocols return the same types. Every column is kept, even if multiple
+/// columns have the same name.
 ///
-/// * You must use prepared statements if you want types to be returned,
-///   otherwise it is always returned as `Value::Bytes`
-/// * There must be only one statement in the query -- i.e. no multiple selects.
-/// * Not sure if nested select statements work.
+/// # Breaking Change
 ///
-/// However, I haven't managed to get MySQL to return `Value`s with proper
-/// return types.
-///
-/// See:
-///
-/// * <https://github.com/go-sql-driver/mysql/issues/407#issuecomment-172583652>
-/// * <https://dev.mysql.com/doc/refman/8.0/en/sql-prepared-statements.html>
+/// This wraps [`RowValues`] instead of an `IndexMap<String, Value>`, so map
+/// methods such as `get(name)` and `keys()` are replaced by
+/// [`RowValues::get_by_name`] and [`RowValues::columns`]. The serialized form
+/// is still a map from column name to value, but columns with the same name
+/// get distinct keys, where previously the last column replaced the others.
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
-pub struct TypedValues(pub IndexMap<String, Value>);
+pub struct TypedValues(pub RowValues<Value>);
 
 impl FromRow for TypedValues {
-    // `column_names_lossy` appears to be a needless_collect to clippy, but it is
-    // needed as `row.unwrap()` consumes `Row`.
-    #[allow(clippy::needless_collect)]
     fn from_row_opt(row: Row) -> Result<Self, FromRowError> {
-        let column_names_lossy = row
-            .columns_ref()
-            .iter()
-            .map(Column::name_str)
-            .map(Cow::into_owned)
-            .collect::<Vec<String>>();
+        // `row.unwrap()` consumes `Row`, so the columns are held separately.
+        let columns = row.columns();
 
         // unwrap here is not `Result::unwrap`, but `Row::unwrap`
-        let values = row.unwrap().into_iter().map(Value::from);
-
-        let values = column_names_lossy
-            .into_iter()
-            .zip(values)
-            .collect::<IndexMap<String, Value>>();
+        let values = columns
+            .iter()
+            .zip(row.unwrap())
+            .map(|(column, value)| {
+                let value = Value::from_column_value(
+                    value,
+                    ColumnType::from(column.column_type()),
+                    ColumnFlags::from(column.flags()),
+                    column.character_set(),
+                );
+                (RowColumn::from(column), value)
+            })
+            .collect::<RowValues<Value>>();
 
         Ok(TypedValues(values))
     }
 }
 
 impl Deref for TypedValues {
-    type Target = IndexMap<String, Value>;
+    type Target = RowValues<Value>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
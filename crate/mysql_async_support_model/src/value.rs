@@ -1,5 +1,6 @@
-use std::str::FromStr;
+use std::{cmp::Ordering, str::FromStr};
 
+use bigdecimal::BigDecimal;
 use chrono::{Duration, NaiveDate, NaiveDateTime};
 use mysql_async::{
     prelude::{ConvIr, FromValue},
@@ -7,22 +8,36 @@ use mysql_async::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::{Column, ColumnFlags, ColumnType};
+
 /// Programmer-friendly model of MySQL [`Value`][mysql_async::Value] type.
 ///
 /// # Note
 ///
-/// * You must use prepared statements if you want types to be returned,
-///   otherwise it is always returned as `Value::Bytes`
-/// * There must be only one statement in the query -- i.e. no multiple selects.
-/// * Not sure if nested select statements work.
+/// With the text protocol, the server sends every value as text. Use
+/// [`Value::from_column_value`] to decode values according to their column's
+/// type, which is what [`TypedValues`] does for both protocols. Converting
+/// from a `mysql_async::Value` without the column type keeps text as
+/// `Value::String`.
+///
+/// # Serialization
 ///
-/// However, I haven't managed to get MySQL to return `Value`s with proper
-/// return types.
+/// Values are serialized untagged, as the inner value only, so deserializing
+/// returns the first variant that the serialized value fits, which is not
+/// always the variant that was serialized:
 ///
-/// See:
+/// * `Date`, `DateTime`, `Timestamp`, and `InvalidDate` are serialized as
+///   strings, and are deserialized as `Value::String`. `DateTime` and
+///   `Timestamp` values with the same date and time serialize identically.
+/// * `Decimal` is serialized as a string, and is deserialized as
+///   `Value::String`.
+/// * `Json` is serialized as the document itself, so scalars and some arrays
+///   and objects are deserialized as other variants. See [`Value::Json`].
 ///
-/// * <https://github.com/go-sql-driver/mysql/issues/407#issuecomment-172583652>
-/// * <https://dev.mysql.com/doc/refman/8.0/en/sql-prepared-statements.html>
+/// Use the column type from the result set's [`Column`]s to parse these back,
+/// such as with [`Value::as_decimal`] or [`NaiveDateTime::from_str`].
+///
+/// [`TypedValues`]: crate::TypedValues
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Value {
@@ -35,7 +50,20 @@ pub enum Value {
     UInt(u64),
     Float(f32),
     Double(f64),
-    /// `DateTime` without a time zone.
+    /// Exact `DECIMAL` or `NUMERIC` value.
+    ///
+    /// This is serialized as a string so that it does not lose precision, and
+    /// is deserialized as `Value::String`, as the two cannot be told apart.
+    /// Use [`Value::as_decimal`] to parse it back.
+    Decimal(BigDecimal),
+    /// `DATE` value.
+    ///
+    /// This is serialized as a `YYYY-MM-DD` string, and is deserialized as
+    /// `Value::String`.
+    ///
+    /// See <https://dev.mysql.com/doc/refman/8.0/en/datetime.html>.
+    Date(NaiveDate),
+    /// `DATETIME` value, without a time zone.
     ///
     /// In `mysql_async` stores this as `Date(u16, u8, u8, u8, u8, u8, u32)`,
     /// correspnding to:
@@ -44,6 +72,12 @@ pub enum Value {
     /// year, month, day, hour, minutes, seconds, micro seconds
     /// ```
     ///
+    /// Date and time values converted without their column type are also
+    /// returned as this variant.
+    ///
+    /// This is serialized as a string in the same format as
+    /// `Value::Timestamp`, and is deserialized as `Value::String`.
+    ///
     /// If you wish to attach timezone information, such as UTC, you may do
     /// something like the following:
     ///
@@ -58,7 +92,27 @@ pub enum Value {
     ///
     /// * <https://docs.rs/mysql_async/latest/mysql_async/enum.Value.html#variant.Date>
     /// * <https://dev.mysql.com/doc/refman/8.0/en/datetime.html>
-    Date(NaiveDateTime),
+    DateTime(NaiveDateTime),
+    /// `TIMESTAMP` value, in the session time zone.
+    ///
+    /// MySQL stores `TIMESTAMP`s in UTC, and converts them to the session's
+    /// `time_zone` when they are read.
+    ///
+    /// This is serialized as a string in the same format as
+    /// `Value::DateTime`, and is deserialized as `Value::String`.
+    ///
+    /// See <https://dev.mysql.com/doc/refman/8.0/en/datetime.html>.
+    Timestamp(NaiveDateTime),
+    /// `DATE`, `DATETIME`, or `TIMESTAMP` value that is not a valid calendar
+    /// date, in MySQL's text format.
+    ///
+    /// MySQL allows zero dates such as `0000-00-00`, and dates with zero parts
+    /// such as `2021-00-15`, unless they are disallowed by the `sql_mode`.
+    ///
+    /// This is deserialized as `Value::String`.
+    ///
+    /// See <https://dev.mysql.com/doc/refman/8.0/en/sql-mode.html#sqlmode_no_zero_date>.
+    InvalidDate(String),
     /// Time offset or duration.
     ///
     /// In `mysql_async` stores this as `Time(bool, u32, u8, u8, u8, u32)`,
@@ -74,64 +128,325 @@ pub enum Value {
     /// * <https://dev.mysql.com/doc/refman/8.0/en/time.html>
     #[serde(with = "value_time_serde")]
     Time(Duration),
+    /// Parsed `JSON` document.
+    ///
+    /// This is serialized inline as the document itself. Use
+    /// [`Value::json_path`] or [`Value::json_pointer`] to access values within
+    /// the document.
+    ///
+    /// Only objects and arrays that do not fit an earlier variant are
+    /// deserialized as `Value::Json`. Other documents are deserialized as the
+    /// first variant they fit:
+    ///
+    /// * `null` as `Value::None`.
+    /// * Booleans as `Value::Bool`, and strings as `Value::String`.
+    /// * Numbers as `Value::Int`, `Value::UInt`, or `Value::Float`.
+    /// * Arrays of integers from `0` to `255`, including empty arrays, as
+    ///   `Value::Bytes`.
+    /// * Arrays of two integers, and objects with only `secs` and `nanos`
+    ///   integers, as `Value::Time`.
+    ///
+    /// Use [`Value::from_column_value`] with the column type to read `JSON`
+    /// columns back as `Value::Json`.
+    Json(serde_json::Value),
+}
+
+impl Value {
+    /// Returns the `Value` for a value from a column with the given type.
+    ///
+    /// Text values are parsed according to the column type, so that values
+    /// sent with the text protocol have the same type as those sent with the
+    /// binary protocol. Values that cannot be parsed are kept as
+    /// `Value::String`.
+    ///
+    /// # Parameters
+    ///
+    /// * `value`: The value from the row.
+    /// * `column_type`: MySQL type of the value's column.
+    /// * `flags`: Attributes of the value's column.
+    /// * `character_set`: Character set ID of the value's column.
+    pub fn from_column_value(
+        value: MySqlValue,
+        column_type: ColumnType,
+        flags: ColumnFlags,
+        character_set: u16,
+    ) -> Self {
+        let bytes = match value {
+            MySqlValue::Bytes(bytes) => bytes,
+            MySqlValue::Date(year, month, day, hour, minutes, seconds, micro_seconds) => {
+                let date_parts = DateParts {
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minutes,
+                    seconds,
+                    micro_seconds,
+                };
+                return date_parts.into_value(column_type);
+            }
+            value => return Self::from(value),
+        };
+
+        match column_type {
+            ColumnType::Null => Value::None,
+            ColumnType::Tiny
+            | ColumnType::Short
+            | ColumnType::Long
+            | ColumnType::LongLong
+            | ColumnType::Int24
+            | ColumnType::Year => Self::text_parse(bytes, |value_str| {
+                if flags.unsigned {
+                    u64::from_str(value_str).map(Value::UInt).ok()
+                } else {
+                    i64::from_str(value_str).map(Value::Int).ok()
+                }
+            }),
+            ColumnType::Float => Self::text_parse(bytes, |value_str| {
+                f32::from_str(value_str).map(Value::Float).ok()
+            }),
+            ColumnType::Double => Self::text_parse(bytes, |value_str| {
+                f64::from_str(value_str).map(Value::Double).ok()
+            }),
+            ColumnType::Date | ColumnType::NewDate => Self::text_parse(bytes, |value_str| {
+                let value = NaiveDate::parse_from_str(value_str, "%Y-%m-%d")
+                    .map(Value::Date)
+                    .unwrap_or_else(|_| Value::InvalidDate(value_str.to_string()));
+                Some(value)
+            }),
+            ColumnType::DateTime
+            | ColumnType::DateTime2
+            | ColumnType::Timestamp
+            | ColumnType::Timestamp2 => Self::text_parse(bytes, |value_str| {
+                let value = NaiveDateTime::parse_from_str(value_str, "%Y-%m-%d %H:%M:%S%.f")
+                    .map(|naive_date_time| match column_type {
+                        ColumnType::Timestamp | ColumnType::Timestamp2 => {
+                            Value::Timestamp(naive_date_time)
+                        }
+                        _ => Value::DateTime(naive_date_time),
+                    })
+                    .unwrap_or_else(|_| Value::InvalidDate(value_str.to_string()));
+                Some(value)
+            }),
+            ColumnType::Time | ColumnType::Time2 => Self::text_parse(bytes, |value_str| {
+                Self::time_parse(value_str).map(Value::Time)
+            }),
+            ColumnType::Bit if bytes.len() <= 8 => Value::UInt(
+                bytes
+                    .iter()
+                    .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+            ),
+            ColumnType::Bit | ColumnType::Geometry => Value::Bytes(bytes),
+            ColumnType::Decimal | ColumnType::NewDecimal => Self::text_parse(bytes, |value_str| {
+                BigDecimal::from_str(value_str).map(Value::Decimal).ok()
+            }),
+            ColumnType::Json => Self::text_parse(bytes, |value_str| {
+                serde_json::from_str(value_str).map(Value::Json).ok()
+            }),
+            ColumnType::Enum
+            | ColumnType::Set
+            | ColumnType::VarChar
+            | ColumnType::VarString
+            | ColumnType::String
+            | ColumnType::TinyBlob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+            | ColumnType::Blob => {
+                if character_set == Column::CHARACTER_SET_BINARY {
+                    Value::Bytes(bytes)
+                } else {
+                    Self::string_or_bytes(bytes)
+                }
+            }
+        }
+    }
+
+    /// Returns this value as an exact decimal, if it is an integer or decimal.
+    ///
+    /// `Value::String`s are parsed, so that decimals read back from their
+    /// serialized form can be used. Floating point values are not converted,
+    /// as they are not exact.
+    pub fn as_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Value::Int(v) => Some(BigDecimal::from(*v)),
+            Value::UInt(v) => Some(BigDecimal::from(*v)),
+            Value::Decimal(v) => Some(v.clone()),
+            Value::String(v) => BigDecimal::from_str(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the exact sum of the values.
+    ///
+    /// As with SQL `SUM`, `Value::None`s are skipped. Returns `None` if any
+    /// other value cannot be converted with [`Value::as_decimal`].
+    pub fn decimal_sum<'v, I>(values: I) -> Option<BigDecimal>
+    where
+        I: IntoIterator<Item = &'v Value>,
+    {
+        values
+            .into_iter()
+            .filter(|value| !matches!(value, Value::None))
+            .try_fold(BigDecimal::from(0), |sum, value| {
+                value.as_decimal().map(|value| sum + value)
+            })
+    }
+
+    /// Compares the exact numeric values of `self` and `other`.
+    ///
+    /// Returns `None` if either value cannot be converted with
+    /// [`Value::as_decimal`].
+    pub fn decimal_cmp(&self, other: &Value) -> Option<Ordering> {
+        Some(self.as_decimal()?.cmp(&other.as_decimal()?))
+    }
+
+    /// Returns the value in the `JSON` document at the given MySQL JSON path,
+    /// such as `$.items[0].name`.
+    ///
+    /// Only member and array index accessors are supported. Returns `None` if
+    /// this is not a `Value::Json`, the path is not supported, or there is no
+    /// value at the path.
+    ///
+    /// See <https://dev.mysql.com/doc/refman/8.0/en/json.html#json-path-syntax>.
+    pub fn json_path(&self, path: &str) -> Option<&serde_json::Value> {
+        let mut json = match self {
+            Value::Json(json) => json,
+            _ => return None,
+        };
+        let mut path = path.trim().strip_prefix('$')?;
+        while !path.is_empty() {
+            if let Some(rest) = path.strip_prefix('.') {
+                let (key, rest) = Self::json_path_key(rest)?;
+                json = json.get(key.as_str())?;
+                path = rest;
+            } else if let Some(rest) = path.strip_prefix('[') {
+                let (index, rest) = rest.split_once(']')?;
+                json = json.get(usize::from_str(index.trim()).ok()?)?;
+                path = rest;
+            } else {
+                return None;
+            }
+        }
+
+        Some(json)
+    }
+
+    /// Returns the value in the `JSON` document at the given JSON pointer,
+    /// such as `/items/0/name`.
+    ///
+    /// Returns `None` if this is not a `Value::Json`, or there is no value at
+    /// the pointer.
+    ///
+    /// See <https://tools.ietf.org/html/rfc6901>.
+    pub fn json_pointer(&self, pointer: &str) -> Option<&serde_json::Value> {
+        match self {
+            Value::Json(json) => json.pointer(pointer),
+            _ => None,
+        }
+    }
+
+    /// Splits a member key from the start of a JSON path, returning the key
+    /// and the rest of the path.
+    ///
+    /// Keys may be bare, or double quoted to contain any characters.
+    fn json_path_key(path: &str) -> Option<(String, &str)> {
+        match path.strip_prefix('"') {
+            Some(quoted) => {
+                let mut key = String::new();
+                let mut chars = quoted.char_indices();
+                while let Some((index, c)) = chars.next() {
+                    match c {
+                        '"' => return Some((key, &quoted[index + 1..])),
+                        '\\' => key.push(chars.next()?.1),
+                        c => key.push(c),
+                    }
+                }
+                None
+            }
+            None => {
+                let end = path.find(['.', '[']).unwrap_or(path.len());
+                let (key, rest) = path.split_at(end);
+                if key.is_empty() {
+                    None
+                } else {
+                    Some((key.to_string(), rest))
+                }
+            }
+        }
+    }
+
+    /// Returns `Value::String` if the bytes are valid UTF-8, otherwise
+    /// `Value::Bytes`.
+    fn string_or_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(value_string) => Value::String(value_string),
+            Err(e) => Value::Bytes(e.into_bytes()),
+        }
+    }
+
+    /// Parses the text value, falling back to `Value::String` if it cannot be
+    /// parsed.
+    fn text_parse<F>(bytes: Vec<u8>, parse: F) -> Self
+    where
+        F: FnOnce(&str) -> Option<Value>,
+    {
+        match String::from_utf8(bytes) {
+            Ok(value_string) => parse(&value_string).unwrap_or(Value::String(value_string)),
+            Err(e) => Value::Bytes(e.into_bytes()),
+        }
+    }
+
+    /// Parses a `TIME` value in the `[-]HHH:MM:SS[.ffffff]` text format.
+    fn time_parse(value_str: &str) -> Option<Duration> {
+        let (is_negative, value_str) = match value_str.strip_prefix('-') {
+            Some(value_str) => (true, value_str),
+            None => (false, value_str),
+        };
+        let mut parts = value_str.splitn(3, ':');
+        let hours = parts.next().and_then(|hours| i64::from_str(hours).ok())?;
+        let minutes = parts
+            .next()
+            .and_then(|minutes| i64::from_str(minutes).ok())?;
+        let seconds = parts.next()?;
+        let (seconds, micro_seconds) = match seconds.split_once('.') {
+            Some((seconds, fraction)) => {
+                // Fractional seconds have up to 6 digits, and are right-padded to
+                // microseconds.
+                let micro_seconds = format!("{:0<6}", fraction);
+                (seconds, i64::from_str(micro_seconds.get(..6)?).ok()?)
+            }
+            None => (seconds, 0),
+        };
+        let seconds = i64::from_str(seconds).ok()?;
+
+        let duration = Duration::hours(hours)
+            + Duration::minutes(minutes)
+            + Duration::seconds(seconds)
+            + Duration::microseconds(micro_seconds);
+        Some(if is_negative { -duration } else { duration })
+    }
 }
 
 impl From<MySqlValue> for Value {
     fn from(value: MySqlValue) -> Self {
         match value {
             MySqlValue::NULL => Value::None,
-            MySqlValue::Bytes(bytes) => {
-                // The following column types are all mapped to Value::Bytes in `mysql_common`
-                // `0.26.0` and `0.27.0`.
-                //
-                // See <https://github.com/blackbeam/rust_mysql_common/blob/v0.27.0/src/value/mod.rs#L398-L416>.
-                //
-                // ```rust,ignore
-                // ColumnType::MYSQL_TYPE_STRING
-                // ColumnType::MYSQL_TYPE_VAR_STRING
-                // ColumnType::MYSQL_TYPE_BLOB
-                // ColumnType::MYSQL_TYPE_TINY_BLOB
-                // ColumnType::MYSQL_TYPE_MEDIUM_BLOB
-                // ColumnType::MYSQL_TYPE_LONG_BLOB
-                // ColumnType::MYSQL_TYPE_SET
-                // ColumnType::MYSQL_TYPE_ENUM
-                // ColumnType::MYSQL_TYPE_DECIMAL
-                // ColumnType::MYSQL_TYPE_VARCHAR
-                // ColumnType::MYSQL_TYPE_BIT
-                // ColumnType::MYSQL_TYPE_NEWDECIMAL
-                // ColumnType::MYSQL_TYPE_GEOMETRY
-                // ColumnType::MYSQL_TYPE_JSON
-                // ```
-                //
-                // We take a naive approach and try to convert the byte array into a string, and
-                // then parse it, because MySQL sends ASCII characters.
-
-                match String::from_utf8(bytes) {
-                    Ok(value_string) => {
-                        let value_str = value_string.as_str();
-                        bool::from_str(value_str)
-                            .map(Value::Bool)
-                            .or_else(|_| i64::from_str(value_str).map(Value::Int))
-                            .or_else(|_| f64::from_str(value_str).map(Value::Double))
-                            .unwrap_or_else(|_| Value::String(value_string))
-                    }
-                    Err(e) => Value::Bytes(e.into_bytes()),
-                }
-            }
+            MySqlValue::Bytes(bytes) => Self::string_or_bytes(bytes),
             MySqlValue::Int(v) => Value::Int(v),
             MySqlValue::UInt(v) => Value::UInt(v),
             MySqlValue::Float(v) => Value::Float(v),
             MySqlValue::Double(v) => Value::Double(v),
             MySqlValue::Date(year, month, day, hour, minutes, seconds, micro_seconds) => {
-                Value::Date(
-                    NaiveDate::from_ymd(i32::from(year), u32::from(month), u32::from(day))
-                        .and_hms_micro(
-                            u32::from(hour),
-                            u32::from(minutes),
-                            u32::from(seconds),
-                            micro_seconds,
-                        ),
-                )
+                let date_parts = DateParts {
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minutes,
+                    seconds,
+                    micro_seconds,
+                };
+                date_parts.into_value(ColumnType::DateTime)
             }
             MySqlValue::Time(is_negative, days, hours, minutes, seconds, micro_seconds) => {
                 let mut duration = Duration::days(i64::from(days))
@@ -150,6 +465,114 @@ impl From<MySqlValue> for Value {
     }
 }
 
+impl From<Value> for MySqlValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::None => MySqlValue::NULL,
+            Value::Bool(v) => MySqlValue::Int(i64::from(v)),
+            Value::String(v) => MySqlValue::Bytes(v.into_bytes()),
+            Value::Bytes(v) => MySqlValue::Bytes(v),
+            Value::Int(v) => MySqlValue::Int(v),
+            Value::UInt(v) => MySqlValue::UInt(v),
+            Value::Float(v) => MySqlValue::Float(v),
+            Value::Double(v) => MySqlValue::Double(v),
+            Value::Decimal(v) => MySqlValue::Bytes(v.to_string().into_bytes()),
+            Value::Date(naive_date) => MySqlValue::from(naive_date),
+            Value::DateTime(naive_date_time) | Value::Timestamp(naive_date_time) => {
+                MySqlValue::from(naive_date_time)
+            }
+            Value::InvalidDate(v) => MySqlValue::Bytes(v.into_bytes()),
+            Value::Json(v) => MySqlValue::Bytes(v.to_string().into_bytes()),
+            Value::Time(duration) => {
+                let is_negative = duration < Duration::zero();
+                let duration = if is_negative { -duration } else { duration };
+
+                let days = duration.num_days();
+                let hours = duration.num_hours() - days * 24;
+                let minutes = duration.num_minutes() - duration.num_hours() * 60;
+                let seconds = duration.num_seconds() - duration.num_minutes() * 60;
+                let micro_seconds = (duration - Duration::seconds(duration.num_seconds()))
+                    .num_microseconds()
+                    .unwrap_or(0);
+
+                // Each component is within its unit's range, and MySQL `TIME` values are
+                // much smaller than `u32::MAX` days.
+                MySqlValue::Time(
+                    is_negative,
+                    days as u32,
+                    hours as u8,
+                    minutes as u8,
+                    seconds as u8,
+                    micro_seconds as u32,
+                )
+            }
+        }
+    }
+}
+
+/// Parts of a binary protocol date and time value.
+pub(crate) struct DateParts {
+    pub(crate) year: u16,
+    pub(crate) month: u8,
+    pub(crate) day: u8,
+    pub(crate) hour: u8,
+    pub(crate) minutes: u8,
+    pub(crate) seconds: u8,
+    pub(crate) micro_seconds: u32,
+}
+
+impl DateParts {
+    /// Returns these parts in MySQL's text format for a column with the given
+    /// type.
+    ///
+    /// Fractional seconds are only included when they are not zero.
+    pub(crate) fn text(&self, column_type: ColumnType) -> String {
+        if let ColumnType::Date | ColumnType::NewDate = column_type {
+            return format!("{:04}-{:02}-{:02}", self.year, self.month, self.day);
+        }
+
+        let mut text = format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minutes, self.seconds
+        );
+        if self.micro_seconds != 0 {
+            text.push_str(&format!(".{:06}", self.micro_seconds));
+        }
+        text
+    }
+
+    /// Returns the `Value` for these parts from a column with the given type.
+    ///
+    /// Parts that are not a valid date and time, such as a zero date, are
+    /// returned as `Value::InvalidDate`.
+    fn into_value(self, column_type: ColumnType) -> Value {
+        let naive_date_time = NaiveDate::from_ymd_opt(
+            i32::from(self.year),
+            u32::from(self.month),
+            u32::from(self.day),
+        )
+        .and_then(|naive_date| {
+            naive_date.and_hms_micro_opt(
+                u32::from(self.hour),
+                u32::from(self.minutes),
+                u32::from(self.seconds),
+                self.micro_seconds,
+            )
+        });
+
+        match (naive_date_time, column_type) {
+            (Some(naive_date_time), ColumnType::Date | ColumnType::NewDate) => {
+                Value::Date(naive_date_time.date())
+            }
+            (Some(naive_date_time), ColumnType::Timestamp | ColumnType::Timestamp2) => {
+                Value::Timestamp(naive_date_time)
+            }
+            (Some(naive_date_time), _) => Value::DateTime(naive_date_time),
+            (None, _) => Value::InvalidDate(self.text(column_type)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ValueIr(MySqlValue);
 
@@ -180,7 +603,7 @@ mod value_time_serde {
         ser::{SerializeStruct, Serializer},
     };
 
-    const FIELDS: &'static [&'static str] = &["secs", "nanos"];
+    const FIELDS: &[&str] = &["secs", "nanos"];
 
     pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -304,9 +727,340 @@ mod value_time_serde {
 
 #[cfg(test)]
 mod tests {
-    use chrono::Duration;
+    use std::{cmp::Ordering, str::FromStr};
+
+    use bigdecimal::BigDecimal;
+    use chrono::{Duration, NaiveDate};
+    use mysql_async::Value as MySqlValue;
 
     use super::Value;
+    use crate::{ColumnFlags, ColumnType};
+
+    const CHARACTER_SET_UTF8MB4: u16 = 255;
+
+    fn from_text(value: &str, column_type: ColumnType, flags: ColumnFlags) -> Value {
+        Value::from_column_value(
+            MySqlValue::Bytes(value.as_bytes().to_vec()),
+            column_type,
+            flags,
+            CHARACTER_SET_UTF8MB4,
+        )
+    }
+
+    #[test]
+    fn from_column_value_string_is_not_sniffed() {
+        let flags = ColumnFlags::default();
+
+        assert_eq!(
+            Value::String(String::from("true")),
+            from_text("true", ColumnType::VarString, flags)
+        );
+        assert_eq!(
+            Value::String(String::from("01234")),
+            from_text("01234", ColumnType::VarString, flags)
+        );
+    }
+
+    #[test]
+    fn from_column_value_numeric_text() {
+        let flags = ColumnFlags::default();
+        let flags_unsigned = ColumnFlags {
+            unsigned: true,
+            ..ColumnFlags::default()
+        };
+
+        assert_eq!(Value::Int(-42), from_text("-42", ColumnType::Long, flags));
+        assert_eq!(
+            Value::UInt(u64::MAX),
+            from_text("18446744073709551615", ColumnType::LongLong, flags_unsigned)
+        );
+        assert_eq!(
+            Value::Double(1.5),
+            from_text("1.5", ColumnType::Double, flags)
+        );
+        assert_eq!(
+            Value::UInt(0x0102),
+            Value::from_column_value(
+                MySqlValue::Bytes(vec![0x01, 0x02]),
+                ColumnType::Bit,
+                flags,
+                CHARACTER_SET_UTF8MB4
+            )
+        );
+    }
+
+    #[test]
+    fn from_column_value_decimal_is_exact() {
+        let value = from_text(
+            "12345678901234.678901",
+            ColumnType::NewDecimal,
+            ColumnFlags::default(),
+        );
+
+        assert_eq!(
+            Value::Decimal(
+                BigDecimal::from_str("12345678901234.678901").expect("Expected decimal to parse.")
+            ),
+            value
+        );
+        assert_eq!(
+            r#""12345678901234.678901""#,
+            serde_json::to_string(&value).expect("Failed to serialize `Value::Decimal`.")
+        );
+    }
+
+    #[test]
+    fn decimal_sum_is_exact() {
+        let values = [
+            Value::Decimal(BigDecimal::from_str("0.1").expect("Expected decimal to parse.")),
+            Value::Decimal(BigDecimal::from_str("0.2").expect("Expected decimal to parse.")),
+            Value::None,
+            Value::Int(-3),
+        ];
+
+        assert_eq!(
+            Some(BigDecimal::from_str("-2.7").expect("Expected decimal to parse.")),
+            Value::decimal_sum(&values)
+        );
+        assert_eq!(None, Value::decimal_sum(&[Value::Double(0.1)]));
+        assert_eq!(
+            Some(Ordering::Equal),
+            Value::Decimal(BigDecimal::from_str("3.00").expect("Expected decimal to parse."))
+                .decimal_cmp(&Value::UInt(3))
+        );
+    }
+
+    #[test]
+    fn from_column_value_json_serializes_inline() {
+        let value = Value::from_column_value(
+            MySqlValue::Bytes(br#"{"items": [{"name": "a.b"}]}"#.to_vec()),
+            ColumnType::Json,
+            ColumnFlags::default(),
+            63,
+        );
+
+        assert_eq!(
+            r#"{"items":[{"name":"a.b"}]}"#,
+            serde_json::to_string(&value).expect("Failed to serialize `Value::Json`.")
+        );
+    }
+
+    #[test]
+    fn json_round_trip_returns_first_variant_that_fits() {
+        let round_trip = |json: serde_json::Value| {
+            let serialized = serde_json::to_string(&Value::Json(json))
+                .expect("Failed to serialize `Value::Json`.");
+            serde_json::from_str::<Value>(&serialized).expect("Failed to deserialize `Value`.")
+        };
+
+        assert_eq!(
+            Value::Json(serde_json::json!({ "a": [1, 2] })),
+            round_trip(serde_json::json!({ "a": [1, 2] }))
+        );
+        assert_eq!(
+            Value::Json(serde_json::json!(["a", 1])),
+            round_trip(serde_json::json!(["a", 1]))
+        );
+        assert_eq!(
+            Value::Bytes(vec![1, 2]),
+            round_trip(serde_json::json!([1, 2]))
+        );
+        assert_eq!(Value::Bytes(Vec::new()), round_trip(serde_json::json!([])));
+        assert_eq!(
+            Value::Time(Duration::seconds(1000)),
+            round_trip(serde_json::json!([1000, 0]))
+        );
+        assert_eq!(Value::Int(-1), round_trip(serde_json::json!(-1)));
+        assert_eq!(Value::Float(1.5), round_trip(serde_json::json!(1.5)));
+        assert_eq!(Value::Bool(true), round_trip(serde_json::json!(true)));
+        assert_eq!(
+            Value::String(String::from("a")),
+            round_trip(serde_json::json!("a"))
+        );
+        assert_eq!(Value::None, round_trip(serde_json::Value::Null));
+    }
+
+    #[test]
+    fn json_path_and_pointer() {
+        let value = Value::Json(serde_json::json!({
+            "items": [{ "name": "a" }, { "full name": "b" }],
+        }));
+        let name_a = serde_json::json!("a");
+        let name_b = serde_json::json!("b");
+
+        assert_eq!(Some(&name_a), value.json_path("$.items[0].name"));
+        assert_eq!(Some(&name_b), value.json_path(r#"$.items[1]."full name""#));
+        assert_eq!(None, value.json_path("$.items[2]"));
+        assert_eq!(None, value.json_path("$.items[*].name"));
+        assert_eq!(Some(&name_a), value.json_pointer("/items/0/name"));
+        assert_eq!(None, Value::String(String::from("{}")).json_path("$"));
+    }
+
+    #[test]
+    fn from_column_value_temporal_text() {
+        let flags = ColumnFlags::default();
+
+        assert_eq!(
+            Value::DateTime(
+                NaiveDate::from_ymd_opt(2021, 5, 30)
+                    .and_then(|naive_date| naive_date.and_hms_micro_opt(12, 6, 53, 445))
+                    .expect("Expected date to be valid.")
+            ),
+            from_text("2021-05-30 12:06:53.000445", ColumnType::DateTime, flags)
+        );
+        assert_eq!(
+            Value::Time(
+                -(Duration::hours(838) + Duration::seconds(59) + Duration::microseconds(500_000))
+            ),
+            from_text("-838:00:59.5", ColumnType::Time, flags)
+        );
+    }
+
+    #[test]
+    fn from_column_value_date_types_are_distinct() {
+        let flags = ColumnFlags::default();
+        let naive_date = NaiveDate::from_ymd_opt(2021, 5, 30).expect("Expected date to be valid.");
+        let naive_date_time = naive_date
+            .and_hms_opt(12, 6, 53)
+            .expect("Expected time to be valid.");
+
+        assert_eq!(
+            Value::Date(naive_date),
+            from_text("2021-05-30", ColumnType::Date, flags)
+        );
+        assert_eq!(
+            Value::DateTime(naive_date_time),
+            from_text("2021-05-30 12:06:53", ColumnType::DateTime, flags)
+        );
+        assert_eq!(
+            Value::Timestamp(naive_date_time),
+            from_text("2021-05-30 12:06:53", ColumnType::Timestamp, flags)
+        );
+        assert_eq!(
+            Value::Date(naive_date),
+            Value::from_column_value(
+                MySqlValue::Date(2021, 5, 30, 0, 0, 0, 0),
+                ColumnType::Date,
+                flags,
+                63
+            )
+        );
+        assert_eq!(
+            Value::Timestamp(naive_date_time),
+            Value::from_column_value(
+                MySqlValue::Date(2021, 5, 30, 12, 6, 53, 0),
+                ColumnType::Timestamp,
+                flags,
+                63
+            )
+        );
+    }
+
+    #[test]
+    fn temporal_values_deserialize_as_string() {
+        let naive_date = NaiveDate::from_ymd_opt(2021, 5, 30).expect("Expected date to be valid.");
+        let naive_date_time = naive_date
+            .and_hms_opt(12, 6, 53)
+            .expect("Expected time to be valid.");
+        let round_trip = |value: &Value| {
+            let serialized = serde_json::to_string(value).expect("Failed to serialize `Value`.");
+            let deserialized =
+                serde_json::from_str::<Value>(&serialized).expect("Failed to deserialize `Value`.");
+            (serialized, deserialized)
+        };
+
+        let (date_time_json, date_time) = round_trip(&Value::DateTime(naive_date_time));
+        let (timestamp_json, timestamp) = round_trip(&Value::Timestamp(naive_date_time));
+        assert_eq!(date_time_json, timestamp_json);
+        assert_eq!(
+            Value::String(String::from("2021-05-30T12:06:53")),
+            date_time
+        );
+        assert_eq!(
+            Value::String(String::from("2021-05-30T12:06:53")),
+            timestamp
+        );
+        assert_eq!(
+            Value::String(String::from("2021-05-30")),
+            round_trip(&Value::Date(naive_date)).1
+        );
+        assert_eq!(
+            Value::String(String::from("0000-00-00")),
+            round_trip(&Value::InvalidDate(String::from("0000-00-00"))).1
+        );
+        assert_eq!(
+            Value::String(String::from("0.1")),
+            round_trip(&Value::Decimal(
+                BigDecimal::from_str("0.1").expect("Expected decimal to parse.")
+            ))
+            .1
+        );
+    }
+
+    #[test]
+    fn from_column_value_zero_date_text() {
+        let flags = ColumnFlags::default();
+
+        assert_eq!(
+            Value::InvalidDate(String::from("0000-00-00")),
+            from_text("0000-00-00", ColumnType::Date, flags)
+        );
+        assert_eq!(
+            Value::InvalidDate(String::from("2021-00-15 00:00:00")),
+            from_text("2021-00-15 00:00:00", ColumnType::DateTime, flags)
+        );
+    }
+
+    #[test]
+    fn from_column_value_zero_date_binary() {
+        let flags = ColumnFlags::default();
+
+        assert_eq!(
+            Value::InvalidDate(String::from("0000-00-00")),
+            Value::from_column_value(
+                MySqlValue::Date(0, 0, 0, 0, 0, 0, 0),
+                ColumnType::Date,
+                flags,
+                63
+            )
+        );
+        assert_eq!(
+            Value::InvalidDate(String::from("2021-02-30 01:02:03.000004")),
+            Value::from_column_value(
+                MySqlValue::Date(2021, 2, 30, 1, 2, 3, 4),
+                ColumnType::DateTime,
+                flags,
+                63
+            )
+        );
+    }
+
+    #[test]
+    fn from_mysql_value_zero_date_does_not_panic() {
+        let value = Value::from(MySqlValue::Date(0, 0, 0, 0, 0, 0, 0));
+
+        assert_eq!(
+            Value::InvalidDate(String::from("0000-00-00 00:00:00")),
+            value
+        );
+        assert_eq!(
+            MySqlValue::Bytes(b"0000-00-00 00:00:00".to_vec()),
+            MySqlValue::from(value)
+        );
+    }
+
+    #[test]
+    fn from_column_value_binary_character_set_is_bytes() {
+        assert_eq!(
+            Value::Bytes(b"abc".to_vec()),
+            Value::from_column_value(
+                MySqlValue::Bytes(b"abc".to_vec()),
+                ColumnType::Blob,
+                ColumnFlags::default(),
+                63
+            )
+        );
+    }
 
     #[test]
     fn serialize_time_positive() {
@@ -352,4 +1106,19 @@ mod tests {
                 .expect("Failed to deserialize `Value::Time`")
         );
     }
+
+    #[test]
+    fn time_into_mysql_value_negative() {
+        let duration = Duration::days(1)
+            + Duration::hours(2)
+            + Duration::minutes(3)
+            + Duration::seconds(4)
+            + Duration::microseconds(5);
+        let value = Value::Time(-duration);
+
+        assert_eq!(
+            MySqlValue::Time(true, 1, 2, 3, 4, 5),
+            MySqlValue::from(value)
+        );
+    }
 }
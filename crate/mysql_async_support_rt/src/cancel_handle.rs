@@ -0,0 +1,170 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use futures::future;
+use mysql_async::Opts;
+use mysql_async_support_model::{Error, QueryError, QueryTarget, Timeouts};
+
+use crate::QueryRunner;
+
+/// Cancels queries that are running on query targets.
+///
+/// Clones of a `CancelHandle` share the same state, so a clone may be set on
+/// a [`QueryRunner`] while another is used to cancel its queries.
+///
+/// Once cancelled, running queries are killed on the server, and queries that
+/// have not started return [`Error::QueryCancelled`].
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle {
+    /// Shared cancellation state.
+    state: Arc<Mutex<CancelState>>,
+}
+
+/// Cancellation state shared between clones of a [`CancelHandle`].
+#[derive(Debug, Default)]
+struct CancelState {
+    /// Whether the handle has been cancelled.
+    cancelled: bool,
+    /// ID to assign to the next running query.
+    query_id_next: u64,
+    /// Queries that are currently running, keyed by their ID.
+    queries_running: HashMap<u64, QueryRunning>,
+}
+
+/// Query that is running on a query target.
+#[derive(Debug)]
+struct QueryRunning {
+    /// Name of the query target.
+    name: String,
+    /// Port of the query target's database server.
+    db_port: u16,
+    /// ID of the connection on the database server that runs the query.
+    connection_id: u32,
+    /// Options of the connection, used to open a side connection.
+    conn_opts: Opts,
+    /// Timeouts used to open the side connection.
+    timeouts: Timeouts,
+}
+
+/// Removes a running query from its [`CancelHandle`] when dropped.
+#[derive(Debug)]
+pub(crate) struct QueryRegistration {
+    /// Shared cancellation state.
+    state: Arc<Mutex<CancelState>>,
+    /// ID of the running query.
+    query_id: u64,
+}
+
+impl CancelHandle {
+    /// Returns a new `CancelHandle` that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether this handle has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state_lock().cancelled
+    }
+
+    /// Cancels all queries, killing those that are running.
+    ///
+    /// Each running query is killed with `KILL QUERY` over a side connection
+    /// opened with the same options, so it goes through the same tunnel.
+    ///
+    /// Returns the names of query targets whose queries were killed, and
+    /// errors for query targets whose queries could not be killed.
+    pub async fn cancel(&self) -> (Vec<String>, Vec<QueryError>) {
+        let queries_running = {
+            let mut state = self.state_lock();
+            state.cancelled = true;
+            state
+                .queries_running
+                .values()
+                .map(|query_running| {
+                    (
+                        query_running.name.clone(),
+                        query_running.db_port,
+                        query_running.connection_id,
+                        query_running.conn_opts.clone(),
+                        query_running.timeouts,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let kill_results = future::join_all(queries_running.into_iter().map(
+            |(name, db_port, connection_id, conn_opts, timeouts)| async move {
+                QueryRunner::query_kill(&conn_opts, connection_id, timeouts)
+                    .await
+                    .map(|()| name.clone())
+                    .map_err(|error| QueryError {
+                        name,
+                        db_port,
+                        error,
+                    })
+            },
+        ))
+        .await;
+
+        kill_results.into_iter().fold(
+            (Vec::new(), Vec::new()),
+            |(mut names_cancelled, mut query_errors), kill_result| {
+                match kill_result {
+                    Ok(name) => names_cancelled.push(name),
+                    Err(query_error) => query_errors.push(query_error),
+                }
+                (names_cancelled, query_errors)
+            },
+        )
+    }
+
+    /// Records a running query so that it is killed when cancelled.
+    ///
+    /// Returns [`Error::QueryCancelled`] if this handle is already cancelled.
+    pub(crate) fn register(
+        &self,
+        query_target: &QueryTarget<'_>,
+        connection_id: u32,
+        conn_opts: &Opts,
+        timeouts: Timeouts,
+    ) -> Result<QueryRegistration, Error> {
+        let mut state = self.state_lock();
+        if state.cancelled {
+            return Err(Error::QueryCancelled);
+        }
+
+        let query_id = state.query_id_next;
+        state.query_id_next += 1;
+        state.queries_running.insert(
+            query_id,
+            QueryRunning {
+                name: query_target.name.to_string(),
+                db_port: query_target.db_port,
+                connection_id,
+                conn_opts: conn_opts.clone(),
+                timeouts,
+            },
+        );
+
+        Ok(QueryRegistration {
+            state: Arc::clone(&self.state),
+            query_id,
+        })
+    }
+
+    fn state_lock(&self) -> MutexGuard<'_, CancelState> {
+        self.state
+            .lock()
+            .expect("`CancelHandle` state lock poisoned.")
+    }
+}
+
+impl Drop for QueryRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.queries_running.remove(&self.query_id);
+        }
+    }
+}
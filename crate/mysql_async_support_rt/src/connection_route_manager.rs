@@ -0,0 +1,141 @@
+use mysql_async_support_model::{ConnectionRoute, Error, QueryTarget, RouteMap, Timeouts};
+
+use crate::SshTunnelManager;
+
+/// Prepares connection routes for query targets.
+#[derive(Debug)]
+pub struct ConnectionRouteManager;
+
+impl ConnectionRouteManager {
+    /// Prepares the connection route for each query target.
+    ///
+    /// The [`RouteMap`] returned by this function should be kept alive until
+    /// all connections are no longer needed.
+    ///
+    /// Callers of this function should appropriately limit the number of query
+    /// targets per route, perhaps by calling [`chunks`].
+    ///
+    /// # Parameters
+    ///
+    /// * `connection_route`: Route to reach the query targets.
+    /// * `query_targets`: Query targets to prepare the route for.
+    /// * `ssh_reconnect_attempts`: Number of times to try to reopen lost SSH
+    ///   sessions.
+    /// * `timeouts`: Maximum durations to open SSH sessions and tunnels.
+    ///
+    /// [`chunks`]: std::slice::chunks
+    pub async fn prepare<'qt>(
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &[&'qt QueryTarget<'qt>],
+        ssh_reconnect_attempts: u32,
+        timeouts: Timeouts,
+    ) -> Result<RouteMap<'qt>, Error> {
+        match connection_route {
+            ConnectionRoute::Direct => Ok(RouteMap::Direct),
+            ConnectionRoute::SshJumpHosts(jump_hosts) => SshTunnelManager::prepare_tunnels(
+                jump_hosts,
+                query_targets,
+                ssh_reconnect_attempts,
+                timeouts,
+            )
+            .await
+            .map(RouteMap::SshTunnels),
+        }
+    }
+
+    /// Groups query targets by the route used to reach them.
+    ///
+    /// Each query target's own [`connection_route`] is used if it is set,
+    /// otherwise `connection_route` is used. Groups are returned in the order
+    /// that their first query target appears in `query_targets`.
+    ///
+    /// [`connection_route`]: QueryTarget::connection_route
+    pub fn group_by_route<'r, 'f: 'r, QueryTargets>(
+        connection_route: &'r ConnectionRoute<'r>,
+        query_targets: QueryTargets,
+    ) -> Vec<(&'r ConnectionRoute<'r>, Vec<&'f QueryTarget<'f>>)>
+    where
+        QueryTargets: IntoIterator<Item = &'f QueryTarget<'f>>,
+    {
+        query_targets.into_iter().fold(
+            Vec::<(&ConnectionRoute<'_>, Vec<&QueryTarget<'_>>)>::new(),
+            |mut route_groups, query_target| {
+                let query_target_route = query_target
+                    .connection_route
+                    .as_ref()
+                    .unwrap_or(connection_route);
+
+                let route_group = route_groups
+                    .iter_mut()
+                    .find(|(route, _)| *route == query_target_route);
+                match route_group {
+                    Some((_, route_query_targets)) => route_query_targets.push(query_target),
+                    None => route_groups.push((query_target_route, vec![query_target])),
+                }
+
+                route_groups
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, path::Path};
+
+    use mysql_async_support_model::{ConnectionRoute, QueryTarget};
+    use ssh_jumper::model::{HostAddress, JumpHostAuthParams};
+
+    use super::ConnectionRouteManager;
+    use crate::test_support::query_target;
+
+    fn ssh_route(jump_host: &'static str) -> ConnectionRoute<'static> {
+        ConnectionRoute::ssh_jump_host(
+            HostAddress::HostName(Cow::Borrowed(jump_host)),
+            JumpHostAuthParams::new(Cow::Borrowed("user"), Cow::Borrowed(Path::new("id_rsa"))),
+        )
+    }
+
+    #[test]
+    fn group_by_route_groups_targets_in_order_of_first_appearance() {
+        let route_a = ssh_route("bastion-a");
+        let route_b = ssh_route("bastion-b");
+        let query_targets = vec![
+            ("t0", None),
+            ("t1", Some(route_b.clone())),
+            ("t2", Some(route_a.clone())),
+            ("t3", Some(ConnectionRoute::Direct)),
+            ("t4", Some(route_b.clone())),
+        ]
+        .into_iter()
+        .map(|(name, connection_route)| {
+            let query_target = query_target(name);
+            match connection_route {
+                Some(connection_route) => query_target.with_connection_route(connection_route),
+                None => query_target,
+            }
+        })
+        .collect::<Vec<QueryTarget<'_>>>();
+
+        let route_groups = ConnectionRouteManager::group_by_route(&route_a, &query_targets);
+        let route_groups = route_groups
+            .into_iter()
+            .map(|(connection_route, query_targets)| {
+                let names = query_targets
+                    .into_iter()
+                    .map(|query_target| query_target.name.as_ref())
+                    .collect::<Vec<&str>>();
+                (connection_route.clone(), names)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (ssh_route("bastion-a"), vec!["t0", "t2"]),
+                (ssh_route("bastion-b"), vec!["t1", "t4"]),
+                (ConnectionRoute::Direct, vec!["t3"]),
+            ],
+            route_groups
+        );
+    }
+}
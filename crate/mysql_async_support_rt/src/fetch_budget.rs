@@ -0,0 +1,64 @@
+use mysql_async::{Row, Value};
+use mysql_async_support_model::ResultLimits;
+
+/// Tracks the rows kept from a query target's results against its
+/// [`ResultLimits`].
+#[derive(Debug)]
+pub(crate) struct FetchBudget {
+    /// Limits on the amount of data kept.
+    result_limits: ResultLimits,
+    /// Number of bytes of row values kept so far.
+    bytes_kept: u64,
+    /// Whether a row has been discarded for exceeding the byte limit.
+    ///
+    /// Once set, no further rows are kept, even if they are small enough to
+    /// fit.
+    bytes_exhausted: bool,
+}
+
+impl FetchBudget {
+    /// Returns a new `FetchBudget` with nothing kept.
+    pub(crate) fn new(result_limits: ResultLimits) -> Self {
+        Self {
+            result_limits,
+            bytes_kept: 0,
+            bytes_exhausted: false,
+        }
+    }
+
+    /// Returns whether the row may be kept in a result set that already has
+    /// `rows_kept` rows, counting its bytes if so.
+    pub(crate) fn row_keep(&mut self, row: &Row, rows_kept: usize) -> bool {
+        if let Some(rows_per_result_set) = self.result_limits.rows_per_result_set {
+            if rows_kept >= rows_per_result_set {
+                return false;
+            }
+        }
+
+        let row_bytes = Self::row_bytes(row);
+        if let Some(bytes_per_target) = self.result_limits.bytes_per_target {
+            if self.bytes_exhausted || self.bytes_kept + row_bytes > bytes_per_target {
+                self.bytes_exhausted = true;
+                return false;
+            }
+        }
+
+        self.bytes_kept += row_bytes;
+        true
+    }
+
+    /// Returns the approximate number of bytes of the row's values.
+    fn row_bytes(row: &Row) -> u64 {
+        (0..row.len())
+            .filter_map(|index| row.as_ref(index))
+            .map(|value| match value {
+                Value::NULL => 0,
+                Value::Bytes(bytes) => bytes.len() as u64,
+                Value::Float(_) => 4,
+                Value::Int(_) | Value::UInt(_) | Value::Double(_) => 8,
+                Value::Date(..) => 11,
+                Value::Time(..) => 12,
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,798 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use futures::{
+    stream::{self, StreamExt},
+    FutureExt,
+};
+use mysql_async::{prelude::FromRow, Pool};
+use mysql_async_support_model::{
+    ConnectionRoute, Error, JumpHost, QueryError, QueryParams, QueryResult, QueryTarget,
+    SshTunnelListener,
+};
+use ssh_jumper::model::{HostAddress, HostSocketParams};
+
+use crate::{
+    retry::retry, timeout::TargetDeadline, ConnectionRouteManager, FnWithPool, QueryRunner,
+    ReconnectingSshSession, SshTunnelManager,
+};
+
+/// Runs queries for one or more query targets, keeping connections warm
+/// between calls.
+///
+/// [`QueryRunner`] opens new SSH sessions, tunnels, and connection pools on
+/// every call. `FleetSession` caches the connection pool for each query
+/// target, together with the tunnel and SSH sessions that it uses, so repeated
+/// queries against the same targets skip the SSH and MySQL handshakes.
+///
+/// Cached connections that have not been used for [`idle_timeout`] are closed
+/// at the start of the next call, or when [`expire_idle`] is called. Call
+/// [`close`] to disconnect all connection pools when the session is no longer
+/// needed.
+///
+/// [`idle_timeout`]: Self::idle_timeout
+/// [`expire_idle`]: Self::expire_idle
+/// [`close`]: Self::close
+pub struct FleetSession {
+    /// Connection limits used when opening connections and running queries.
+    pub query_runner: QueryRunner,
+    /// Duration after which unused connections are closed.
+    pub idle_timeout: Duration,
+    /// Connection pool for each query target, keyed by query target name.
+    target_pools: Mutex<HashMap<String, TargetPool>>,
+    /// Pools that were replaced while in use, disconnected once they are no
+    /// longer in use.
+    target_pools_retired: Mutex<Vec<TargetPool>>,
+    /// SSH session for each connection route that tunnels are opened through.
+    ssh_sessions: Mutex<Vec<SshSessionCached>>,
+}
+
+/// Connection pool to a query target, and the tunnel it connects through.
+#[derive(Debug)]
+struct TargetPool {
+    /// The query target that the pool connects to.
+    query_target: QueryTarget<'static>,
+    /// The route used to reach the query target.
+    connection_route: ConnectionRoute<'static>,
+    /// Connection pool to the database server.
+    pool: Pool,
+    /// Tunnel to the database server, `None` for direct connections.
+    ///
+    /// This is shared with each [`PoolLease`] of the pool, so that errors in
+    /// the tunnel are reported to the call that uses it, even after the pool
+    /// is replaced.
+    ssh_tunnel_listener: Option<Arc<SshTunnelListener>>,
+    /// When the pool was last used.
+    ///
+    /// This is shared with each [`PoolLease`] of the pool, so the pool is in
+    /// use while there is more than one reference to it.
+    last_used: Arc<Mutex<Instant>>,
+}
+
+/// Connection pool handed out to a call, which marks the pool as in use until
+/// it is dropped.
+#[derive(Debug)]
+struct PoolLease {
+    /// Connection pool to the database server.
+    pool: Pool,
+    /// Tunnel to the database server, shared with its [`TargetPool`].
+    ssh_tunnel_listener: Option<Arc<SshTunnelListener>>,
+    /// When the pool was last used, shared with its [`TargetPool`].
+    last_used: Arc<Mutex<Instant>>,
+    /// Time spent opening the pool in this call, which counts towards the
+    /// [`target`] timeout.
+    ///
+    /// [`target`]: mysql_async_support_model::Timeouts::target
+    setup_elapsed: Duration,
+}
+
+/// SSH session to a connection route that is kept open between calls.
+struct SshSessionCached {
+    /// The route that the session connects through.
+    connection_route: ConnectionRoute<'static>,
+    /// The SSH session, shared with the tunnels opened through it.
+    ssh_session: Arc<ReconnectingSshSession>,
+    /// When tunnels were last opened through the session.
+    last_used: Instant,
+}
+
+impl FleetSession {
+    /// Default duration after which unused connections are closed.
+    pub const IDLE_TIMEOUT_DEFAULT: Duration = Duration::from_secs(300);
+
+    /// Returns a new `FleetSession`.
+    pub fn new(query_runner: QueryRunner, idle_timeout: Duration) -> Self {
+        Self {
+            query_runner,
+            idle_timeout,
+            target_pools: Mutex::new(HashMap::new()),
+            target_pools_retired: Mutex::new(Vec::new()),
+            ssh_sessions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queries a database through the given connection route.
+    ///
+    /// If the query target has its own connection route, that is used instead.
+    pub async fn query<T>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+    ) -> Result<QueryResult<T>, Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        self.query_with_params(connection_route, query_target, sql_text, QueryParams::Empty)
+            .await
+    }
+
+    /// Queries a database through the given connection route, binding the
+    /// parameters to the prepared statement.
+    ///
+    /// If the query target has its own connection route, that is used instead.
+    pub async fn query_with_params<T>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+    ) -> Result<QueryResult<T>, Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let query_targets = std::slice::from_ref(query_target);
+        let (mut pools, mut pool_errors) =
+            self.pools_prepare(connection_route, query_targets).await;
+
+        match (pools.pop(), pool_errors.pop()) {
+            (Some((query_target, pool_lease)), _) => {
+                let target_deadline = pool_lease.target_deadline(self.query_runner.timeouts.target);
+                let query_result = self.query_runner.query_run(
+                    &pool_lease.pool,
+                    query_target,
+                    sql_text,
+                    query_params,
+                );
+                TargetDeadline::run(target_deadline, query_result)
+                    .await
+                    .map_err(|error| pool_lease.tunnel_error_take().unwrap_or(error))
+            }
+            (None, Some((_, error))) => Err(error),
+            (None, None) => Err(Error::SshConnInit),
+        }
+    }
+
+    /// Queries multiple query targets with the same query.
+    ///
+    /// Connection pools are reused from previous calls where possible. New
+    /// pools are opened in the same way as [`QueryRunner::query_multi`].
+    pub async fn query_multi<T>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &[QueryTarget<'_>],
+        sql_text: &str,
+    ) -> (Vec<QueryResult<T>>, Vec<QueryError>)
+    where
+        T: FromRow + Send + 'static,
+    {
+        self.query_multi_with_params(connection_route, query_targets, sql_text, |_| {
+            QueryParams::Empty
+        })
+        .await
+    }
+
+    /// Queries multiple query targets with the same query, binding each
+    /// target's parameters to the prepared statement.
+    ///
+    /// See [`QueryRunner::query_multi_with_params`].
+    pub async fn query_multi_with_params<T, QueryParamsFn>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &[QueryTarget<'_>],
+        sql_text: &str,
+        query_params_fn: QueryParamsFn,
+    ) -> (Vec<QueryResult<T>>, Vec<QueryError>)
+    where
+        T: FromRow + Send + 'static,
+        QueryParamsFn: Fn(&QueryTarget<'_>) -> QueryParams,
+    {
+        let query_params_fn = &query_params_fn;
+        let (pools, pool_errors) = self.pools_prepare(connection_route, query_targets).await;
+        let query_errors = pool_errors
+            .into_iter()
+            .map(|(query_target, error)| QueryError::new(query_target, error))
+            .collect::<Vec<QueryError>>();
+
+        stream::iter(pools)
+            .map(|(query_target, pool_lease)| async move {
+                let target_deadline = pool_lease.target_deadline(self.query_runner.timeouts.target);
+                let query_result = self.query_runner.query_run(
+                    &pool_lease.pool,
+                    query_target,
+                    sql_text,
+                    query_params_fn(query_target),
+                );
+                TargetDeadline::run(target_deadline, query_result)
+                    .await
+                    .map_err(|error| {
+                        QueryError::new(
+                            query_target,
+                            pool_lease.tunnel_error_take().unwrap_or(error),
+                        )
+                    })
+            })
+            .buffered(self.concurrent_limit())
+            .fold(
+                (Vec::with_capacity(query_targets.len()), query_errors),
+                |(mut query_results, mut query_errors), result| async {
+                    match result {
+                        Ok(query_result) => query_results.push(query_result),
+                        Err(query_error) => query_errors.push(query_error),
+                    }
+                    (query_results, query_errors)
+                },
+            )
+            .await
+    }
+
+    /// Runs queries against multiple query targets.
+    ///
+    /// The pool passed to `queries` is shared with later calls, so it must not
+    /// be disconnected by `queries`.
+    ///
+    /// `queries` cannot be run as a dry run, so if [`dry_run`] is set, it is
+    /// not run, and [`Error::DryRunUnsupported`] is returned for each query
+    /// target.
+    ///
+    /// [`dry_run`]: QueryRunner::dry_run
+    pub async fn exec_multi<'f, Queries>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        queries: Queries,
+    ) -> (
+        Vec<(&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Output)>,
+        Vec<(&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Error)>,
+    )
+    where
+        Queries: FnWithPool<'f> + Copy,
+        <Queries as FnWithPool<'f>>::Error: From<Error>,
+    {
+        if self.query_runner.dry_run.is_some() {
+            let exec_errors = query_targets
+                .iter()
+                .map(|query_target| {
+                    (
+                        query_target,
+                        <Queries as FnWithPool<'f>>::Error::from(Error::DryRunUnsupported),
+                    )
+                })
+                .collect();
+            return (Vec::new(), exec_errors);
+        }
+
+        let (pools, pool_errors) = self.pools_prepare(connection_route, query_targets).await;
+        let exec_errors = pool_errors
+            .into_iter()
+            .map(|(query_target, error)| {
+                (
+                    query_target,
+                    <Queries as FnWithPool<'f>>::Error::from(error),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        stream::iter(pools)
+            .map(|(query_target, pool_lease)| async move {
+                let target_deadline = pool_lease.target_deadline(self.query_runner.timeouts.target);
+                let exec_result = queries
+                    .call(pool_lease.pool.clone())
+                    .map(|(_pool, result)| result);
+                TargetDeadline::run(target_deadline, exec_result)
+                    .await
+                    .map(|exec_result| (query_target, exec_result))
+                    .map_err(|exec_error| {
+                        let exec_error = pool_lease
+                            .tunnel_error_take()
+                            .map(<Queries as FnWithPool<'f>>::Error::from)
+                            .unwrap_or(exec_error);
+                        (query_target, exec_error)
+                    })
+            })
+            .buffered(self.concurrent_limit())
+            .fold(
+                (Vec::with_capacity(query_targets.len()), exec_errors),
+                |(mut exec_results, mut exec_errors), result| async {
+                    match result {
+                        Ok(exec_result) => exec_results.push(exec_result),
+                        Err(exec_error) => exec_errors.push(exec_error),
+                    }
+                    (exec_results, exec_errors)
+                },
+            )
+            .await
+    }
+
+    /// Closes connections that have not been used for [`idle_timeout`].
+    ///
+    /// Pools that are in use by a running call are not closed, and their idle
+    /// time starts when the call finishes. SSH sessions that have not had
+    /// tunnels opened through them for [`idle_timeout`] are no longer reused,
+    /// and are closed when none of their tunnels are in use.
+    ///
+    /// [`idle_timeout`]: Self::idle_timeout
+    pub async fn expire_idle(&self) {
+        let now = Instant::now();
+        let mut target_pools_expired = {
+            let mut target_pools = self.target_pools_lock();
+            let (target_pools_expired, target_pools_active) = std::mem::take(&mut *target_pools)
+                .into_iter()
+                .partition::<HashMap<_, _>, _>(|(_, target_pool)| {
+                    target_pool.idle_expired(now, self.idle_timeout)
+                });
+            *target_pools = target_pools_active;
+
+            target_pools_expired.into_values().collect::<Vec<_>>()
+        };
+        {
+            let mut target_pools_retired = self.target_pools_retired_lock();
+            let (target_pools_unused, target_pools_in_use) =
+                std::mem::take(&mut *target_pools_retired)
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|target_pool| !target_pool.in_use());
+            *target_pools_retired = target_pools_in_use;
+            target_pools_expired.extend(target_pools_unused);
+        }
+        self.ssh_sessions_lock().retain(|ssh_session_cached| {
+            Arc::strong_count(&ssh_session_cached.ssh_session) > 1
+                || now.duration_since(ssh_session_cached.last_used) < self.idle_timeout
+        });
+
+        // Failing to cleanly disconnect a pool that is no longer needed is not
+        // actionable, so the error is discarded.
+        let _ = self.target_pools_disconnect(target_pools_expired).await;
+    }
+
+    /// Disconnects all connection pools, and closes all tunnels and SSH
+    /// sessions.
+    pub async fn close(self) -> Result<(), Error> {
+        let target_pools = std::mem::take(&mut *self.target_pools_lock());
+        let target_pools_retired = std::mem::take(&mut *self.target_pools_retired_lock());
+        self.ssh_sessions_lock().clear();
+        self.target_pools_disconnect(target_pools.into_values().chain(target_pools_retired))
+            .await
+    }
+
+    /// Returns a lease on a connection pool for each query target, and errors
+    /// for query targets that could not be connected to.
+    ///
+    /// Each pool is in use until its lease is dropped.
+    async fn pools_prepare<'f>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+    ) -> (
+        Vec<(&'f QueryTarget<'f>, PoolLease)>,
+        Vec<(&'f QueryTarget<'f>, Error)>,
+    ) {
+        self.expire_idle().await;
+
+        let mut pools = Vec::with_capacity(query_targets.len());
+        let query_targets_uncached = {
+            let mut target_pools = self.target_pools_lock();
+            query_targets
+                .iter()
+                .filter(|query_target| {
+                    let connection_route = query_target
+                        .connection_route
+                        .as_ref()
+                        .unwrap_or(connection_route);
+                    match target_pools.get_mut(query_target.name.as_ref()) {
+                        Some(target_pool)
+                            if target_pool.matches(query_target, connection_route) =>
+                        {
+                            pools.push((*query_target, target_pool.lease(Duration::ZERO)));
+                            false
+                        }
+                        _ => true,
+                    }
+                })
+                .collect::<Vec<&QueryTarget<'_>>>()
+        };
+
+        let route_groups =
+            ConnectionRouteManager::group_by_route(connection_route, query_targets_uncached);
+        let (target_pools_new, pool_errors) =
+            stream::iter(self.query_runner.route_chunks(&route_groups))
+                .map(|(connection_route, query_targets_chunk)| {
+                    self.pools_create(connection_route, query_targets_chunk)
+                })
+                .buffered(self.query_runner.ssh_concurrent_limit)
+                .fold(
+                    (Vec::new(), Vec::new()),
+                    |(mut target_pools_new, mut pool_errors),
+                     (target_pools_chunk, pool_errors_chunk)| async move {
+                        target_pools_new.extend(target_pools_chunk);
+                        pool_errors.extend(pool_errors_chunk);
+
+                        (target_pools_new, pool_errors)
+                    },
+                )
+                .await;
+
+        let (target_pools_unused, target_pools_in_use) = {
+            let mut target_pools = self.target_pools_lock();
+            target_pools_new
+                .into_iter()
+                .filter_map(|(query_target, target_pool, setup_elapsed)| {
+                    pools.push((query_target, target_pool.lease(setup_elapsed)));
+                    target_pools.insert(query_target.name.to_string(), target_pool)
+                })
+                .partition::<Vec<TargetPool>, _>(|target_pool| !target_pool.in_use())
+        };
+        // Pools for query targets whose details have changed, or that were created by a
+        // concurrent call, are replaced. Pools that are still in use are disconnected
+        // by `expire_idle` once their calls finish.
+        self.target_pools_retired_lock().extend(target_pools_in_use);
+        let _ = self.target_pools_disconnect(target_pools_unused).await;
+
+        (pools, pool_errors)
+    }
+
+    /// Opens an SSH session for the chunk of query targets if needed, and
+    /// creates a connection pool for each query target.
+    ///
+    /// Opening each pool is bounded by the [`target`] timeout, which starts
+    /// when the chunk starts. Each pool is returned with the time spent
+    /// opening it, so that the query phase is bounded by what is left.
+    ///
+    /// [`target`]: mysql_async_support_model::Timeouts::target
+    async fn pools_create<'f>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &[&'f QueryTarget<'f>],
+    ) -> (
+        Vec<(&'f QueryTarget<'f>, TargetPool, Duration)>,
+        Vec<(&'f QueryTarget<'f>, Error)>,
+    ) {
+        let target_deadline = TargetDeadline::start(self.query_runner.timeouts.target);
+        let ssh_session = match connection_route {
+            ConnectionRoute::Direct => None,
+            ConnectionRoute::SshJumpHosts(jump_hosts) => {
+                let ssh_session = TargetDeadline::run(
+                    target_deadline,
+                    self.ssh_session_get(connection_route, jump_hosts),
+                )
+                .await;
+                match ssh_session {
+                    Ok(ssh_session) => Some(ssh_session),
+                    Err(e) => {
+                        let mut error = Some(e);
+                        let errors = query_targets
+                            .iter()
+                            .map(|query_target| {
+                                (*query_target, error.take().unwrap_or(Error::SshConnInit))
+                            })
+                            .collect::<Vec<_>>();
+                        return (vec![], errors);
+                    }
+                }
+            }
+        };
+
+        let ssh_session = &ssh_session;
+        stream::iter(query_targets.iter().copied())
+            .then(|query_target| async move {
+                let target_pool = TargetDeadline::run(target_deadline, async {
+                    let ssh_tunnel_listener = match ssh_session {
+                        Some(ssh_session) => {
+                            let target_socket = HostSocketParams {
+                                address: query_target.db_address.into_static(),
+                                port: query_target.db_port,
+                            };
+                            let ssh_tunnel_listener = SshTunnelManager::open_tunnel_listener(
+                                Arc::clone(ssh_session),
+                                target_socket,
+                            )
+                            .await?;
+                            Some(Arc::new(ssh_tunnel_listener))
+                        }
+                        None => None,
+                    };
+                    let db_socket = ssh_tunnel_listener
+                        .as_ref()
+                        .map(|ssh_tunnel_listener| {
+                            Self::local_socket(ssh_tunnel_listener.local_socket_addr)
+                        })
+                        .unwrap_or_else(|| query_target.db_socket());
+                    let pool = self
+                        .query_runner
+                        .sql_over_ssh
+                        .db_pool_initialize(
+                            db_socket,
+                            query_target.db_schema_cred.clone(),
+                            self.query_runner.db_conn_opts(query_target),
+                        )
+                        .await?;
+
+                    Ok(TargetPool {
+                        query_target: query_target.clone().into_static(),
+                        connection_route: connection_route.clone().into_static(),
+                        pool,
+                        ssh_tunnel_listener,
+                        last_used: Arc::new(Mutex::new(Instant::now())),
+                    })
+                })
+                .await
+                .map_err(|error| (query_target, error))?;
+                let setup_elapsed = target_deadline
+                    .map(TargetDeadline::elapsed)
+                    .unwrap_or_default();
+
+                Ok((query_target, target_pool, setup_elapsed))
+            })
+            .fold(
+                (Vec::new(), Vec::new()),
+                |(mut target_pools, mut pool_errors), result| async {
+                    match result {
+                        Ok(target_pool) => target_pools.push(target_pool),
+                        Err(pool_error) => pool_errors.push(pool_error),
+                    }
+                    (target_pools, pool_errors)
+                },
+            )
+            .await
+    }
+
+    /// Returns the cached SSH session for the connection route, opening and
+    /// caching a new one if there is none.
+    async fn ssh_session_get(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        jump_hosts: &[JumpHost<'_>],
+    ) -> Result<Arc<ReconnectingSshSession>, Error> {
+        let ssh_session_cached = self
+            .ssh_sessions_lock()
+            .iter_mut()
+            .find(|ssh_session_cached| &ssh_session_cached.connection_route == connection_route)
+            .map(|ssh_session_cached| {
+                ssh_session_cached.last_used = Instant::now();
+                Arc::clone(&ssh_session_cached.ssh_session)
+            });
+        if let Some(ssh_session) = ssh_session_cached {
+            return Ok(ssh_session);
+        }
+
+        let jump_hosts = jump_hosts
+            .iter()
+            .cloned()
+            .map(JumpHost::into_static)
+            .collect::<Vec<_>>();
+        let ssh_session = retry(&self.query_runner.retry_policies.ssh_setup, || {
+            ReconnectingSshSession::open(
+                jump_hosts.clone(),
+                self.query_runner.ssh_reconnect_attempts,
+                self.query_runner.timeouts,
+            )
+        })
+        .await
+        .map(Arc::new)?;
+
+        // A concurrent call may have opened a session for the same route, in which
+        // case it is replaced, and closed when its tunnels are dropped.
+        let mut ssh_sessions = self.ssh_sessions_lock();
+        ssh_sessions
+            .retain(|ssh_session_cached| &ssh_session_cached.connection_route != connection_route);
+        ssh_sessions.push(SshSessionCached {
+            connection_route: connection_route.clone().into_static(),
+            ssh_session: Arc::clone(&ssh_session),
+            last_used: Instant::now(),
+        });
+
+        Ok(ssh_session)
+    }
+
+    /// Disconnects each pool, then closes its tunnel.
+    ///
+    /// Returns the first error that occurred, after attempting to disconnect
+    /// all pools.
+    async fn target_pools_disconnect<TargetPools>(
+        &self,
+        target_pools: TargetPools,
+    ) -> Result<(), Error>
+    where
+        TargetPools: IntoIterator<Item = TargetPool>,
+    {
+        stream::iter(target_pools)
+            .map(|target_pool| async move {
+                let TargetPool {
+                    pool,
+                    ssh_tunnel_listener,
+                    ..
+                } = target_pool;
+                let result = pool.disconnect().await.map_err(Error::MySqlPoolDisconnect);
+
+                // The tunnel must be kept open until the pool is disconnected.
+                drop(ssh_tunnel_listener);
+
+                result
+            })
+            .buffer_unordered(self.concurrent_limit())
+            .fold(Ok(()), |result_all, result| async move {
+                result_all.and(result)
+            })
+            .await
+    }
+
+    fn local_socket(local_socket_addr: SocketAddr) -> HostSocketParams<'static> {
+        HostSocketParams {
+            address: HostAddress::IpAddr(local_socket_addr.ip()),
+            port: local_socket_addr.port(),
+        }
+    }
+
+    /// Maximum number of query targets to run queries on concurrently.
+    fn concurrent_limit(&self) -> usize {
+        self.query_runner.ssh_concurrent_limit * self.query_runner.tunnels_per_ssh_connection
+    }
+
+    fn target_pools_lock(&self) -> MutexGuard<'_, HashMap<String, TargetPool>> {
+        self.target_pools
+            .lock()
+            .expect("`FleetSession` target pools lock poisoned.")
+    }
+
+    fn target_pools_retired_lock(&self) -> MutexGuard<'_, Vec<TargetPool>> {
+        self.target_pools_retired
+            .lock()
+            .expect("`FleetSession` retired target pools lock poisoned.")
+    }
+
+    fn ssh_sessions_lock(&self) -> MutexGuard<'_, Vec<SshSessionCached>> {
+        self.ssh_sessions
+            .lock()
+            .expect("`FleetSession` SSH sessions lock poisoned.")
+    }
+}
+
+impl TargetPool {
+    /// Returns whether this pool was created for the given query target and
+    /// route.
+    fn matches(
+        &self,
+        query_target: &QueryTarget<'_>,
+        connection_route: &ConnectionRoute<'_>,
+    ) -> bool {
+        &self.query_target == query_target && &self.connection_route == connection_route
+    }
+
+    /// Returns a lease on the pool, which marks it as in use until dropped.
+    ///
+    /// `setup_elapsed` is the time spent opening the pool in this call, which
+    /// is zero for a cached pool.
+    fn lease(&self, setup_elapsed: Duration) -> PoolLease {
+        PoolLease {
+            pool: self.pool.clone(),
+            ssh_tunnel_listener: self.ssh_tunnel_listener.clone(),
+            last_used: Arc::clone(&self.last_used),
+            setup_elapsed,
+        }
+    }
+
+    /// Returns whether a [`PoolLease`] of this pool has not been dropped.
+    fn in_use(&self) -> bool {
+        Arc::strong_count(&self.last_used) > 1
+    }
+
+    /// Returns whether this pool is not in use, and has not been used for
+    /// `idle_timeout`.
+    fn idle_expired(&self, now: Instant, idle_timeout: Duration) -> bool {
+        let last_used = *self
+            .last_used
+            .lock()
+            .expect("`TargetPool` last used lock poisoned.");
+        !self.in_use() && now.duration_since(last_used) >= idle_timeout
+    }
+}
+
+impl PoolLease {
+    /// Returns the deadline for running queries on the pool, `None` if there
+    /// is no deadline.
+    ///
+    /// Time spent opening the pool counts towards the deadline, but time spent
+    /// waiting to run the query does not.
+    fn target_deadline(&self, target: Option<Duration>) -> Option<TargetDeadline> {
+        TargetDeadline::resume(target, self.setup_elapsed)
+    }
+
+    /// Returns the error that occurred in the pool's tunnel, if any.
+    fn tunnel_error_take(&self) -> Option<Error> {
+        self.ssh_tunnel_listener
+            .as_deref()
+            .and_then(SshTunnelListener::take_error)
+    }
+}
+
+impl Drop for PoolLease {
+    fn drop(&mut self) {
+        if let Ok(mut last_used) = self.last_used.lock() {
+            *last_used = Instant::now();
+        }
+    }
+}
+
+impl Default for FleetSession {
+    fn default() -> Self {
+        Self::new(QueryRunner::default(), Self::IDLE_TIMEOUT_DEFAULT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::Ipv4Addr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use mysql_async::{OptsBuilder, Pool};
+    use mysql_async_support_model::{ConnectionRoute, Error, SshTunnelListener};
+
+    use super::TargetPool;
+    use crate::test_support::query_target;
+
+    fn target_pool(ssh_tunnel_listener: Option<Arc<SshTunnelListener>>) -> TargetPool {
+        TargetPool {
+            query_target: query_target("target"),
+            connection_route: ConnectionRoute::Direct,
+            pool: Pool::new(OptsBuilder::default()),
+            ssh_tunnel_listener,
+            last_used: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    #[test]
+    fn pool_lease_keeps_pool_from_expiring() {
+        let idle_timeout = Duration::from_secs(60);
+        let target_pool = target_pool(None);
+
+        let pool_lease = target_pool.lease(Duration::ZERO);
+        let later = Instant::now() + idle_timeout;
+        assert!(target_pool.in_use());
+        assert!(!target_pool.idle_expired(later, idle_timeout));
+
+        drop(pool_lease);
+        let released = Instant::now();
+        assert!(!target_pool.in_use());
+        assert!(!target_pool.idle_expired(released, idle_timeout));
+        assert!(target_pool.idle_expired(released + idle_timeout, idle_timeout));
+    }
+
+    #[test]
+    fn pool_lease_takes_tunnel_error_after_pool_is_replaced() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("Failed to build runtime.");
+        let _runtime_guard = runtime.enter();
+        let ssh_tunnel_listener = SshTunnelListener::new(
+            (Ipv4Addr::LOCALHOST, 3306).into(),
+            tokio::spawn(async {}),
+            Arc::new(Mutex::new(Some(Error::SshConnInit))),
+        );
+        let target_pool = target_pool(Some(Arc::new(ssh_tunnel_listener)));
+
+        let pool_lease = target_pool.lease(Duration::ZERO);
+        drop(target_pool);
+
+        assert!(matches!(
+            pool_lease.tunnel_error_take(),
+            Some(Error::SshConnInit)
+        ));
+        assert!(pool_lease.tunnel_error_take().is_none());
+    }
+}
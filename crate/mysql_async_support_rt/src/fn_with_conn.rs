@@ -0,0 +1,28 @@
+use mysql_async::Conn;
+use mysql_async_support_model::Error;
+
+/// Trait for an async function that runs on a connection, with a restricted
+/// lifetime scope.
+pub trait FnWithConn<'conn> {
+    type Output;
+    type Error: From<Error>;
+    type Fut: std::future::Future<Output = (Conn, Result<Self::Output, Self::Error>)> + 'conn;
+
+    /// Runs the function
+    fn call(self, conn: Conn) -> Self::Fut;
+}
+
+impl<'conn, F, Fut, T, E> FnWithConn<'conn> for F
+where
+    F: FnOnce(Conn) -> Fut,
+    E: From<Error>,
+    Fut: std::future::Future<Output = (Conn, Result<T, E>)> + 'conn,
+{
+    type Error = E;
+    type Fut = Fut;
+    type Output = T;
+
+    fn call(self, conn: Conn) -> Fut {
+        self(conn)
+    }
+}
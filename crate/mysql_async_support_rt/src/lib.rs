@@ -1,9 +1,24 @@
 pub use crate::{
-    fn_with_pool::FnWithPool, query_runner::QueryRunner, sql_over_ssh::SqlOverSsh,
-    ssh_tunnel_manager::SshTunnelManager,
+    cancel_handle::CancelHandle, connection_route_manager::ConnectionRouteManager,
+    fleet_session::FleetSession, fn_with_conn::FnWithConn, fn_with_pool::FnWithPool,
+    query_runner::QueryRunner, reconnecting_ssh_session::ReconnectingSshSession,
+    script_runner::ScriptRunner, sql_over_ssh::SqlOverSsh, ssh_tunnel_manager::SshTunnelManager,
+    transaction_runner::TransactionRunner,
 };
 
+mod cancel_handle;
+mod connection_route_manager;
+mod fetch_budget;
+mod fleet_session;
+mod fn_with_conn;
 mod fn_with_pool;
 mod query_runner;
+mod reconnecting_ssh_session;
+mod retry;
+mod script_runner;
 mod sql_over_ssh;
 mod ssh_tunnel_manager;
+#[cfg(test)]
+mod test_support;
+mod timeout;
+mod transaction_runner;
@@ -1,17 +1,50 @@
-use futures::stream::{self, StreamExt};
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use futures::{
+    channel::mpsc,
+    future,
+    stream::{self, Stream, StreamExt},
+    SinkExt, TryFutureExt,
+};
 use mysql_async::{
-    prelude::{FromRow, Queryable},
-    BinaryProtocol,
+    prelude::{FromRow, Protocol, Queryable},
+    Conn, DriverError, FromRowError, Opts, ServerError, Statement, TxOpts,
 };
 use mysql_async_support_model::{
-    Error, QueryError, QueryResult, QueryTarget, ResultSet, SshTunnelMap,
+    Column, ConnectionRoute, DbConnOpts, DryRun, Error, QueryError, QueryParams, QueryProtocol,
+    QueryResult, QueryTarget, ResultLimits, ResultSet, RetryPolicies, RouteMap, RowStreamItem,
+    SqlScript, SqlStatement, Timeouts,
 };
-use ssh_jumper::{
-    model::{HostAddress, HostSocketParams, JumpHostAuthParams, SshTunnelParams},
-    SshJumper,
+
+use crate::{
+    cancel_handle::QueryRegistration,
+    fetch_budget::FetchBudget,
+    retry::{self, retry},
+    timeout::{timeout, TargetDeadline},
+    CancelHandle, ConnectionRouteManager, FnWithPool, SqlOverSsh,
 };
 
-use crate::{FnWithPool, SqlOverSsh, SshTunnelManager};
+/// Result sets of a dry run query, and of its before-image query.
+type DryRunResultSets<T> = (Vec<ResultSet<T>>, Option<Vec<ResultSet<T>>>);
+
+/// Sends rows streamed from a query.
+type RowSender<T> = mpsc::Sender<Result<RowStreamItem<T>, Error>>;
+
+/// Progress of streaming a query's rows, kept across attempts.
+#[derive(Debug, Default)]
+struct RowStreamProgress {
+    /// Whether any item has been sent, after which attempts are not retried.
+    items_sent: bool,
+    /// Options and ID of the connection while the query runs, so the query
+    /// can be killed if the target deadline elapses.
+    query_running: Option<(Opts, u32)>,
+}
+
+/// Output or error of running queries on a query target.
+type ExecOutcome<'f, Queries> = Result<
+    (&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Output),
+    (&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Error),
+>;
 
 /// Runs queries for one or more query targets.
 #[derive(Clone)]
@@ -22,11 +55,92 @@ pub struct QueryRunner {
     pub ssh_concurrent_limit: usize,
     /// Maximum number of tunnels per SSH connection.
     pub tunnels_per_ssh_connection: usize,
+    /// Number of times to try to reopen an SSH session that is lost mid-run.
+    pub ssh_reconnect_attempts: u32,
+    /// Options for connections to database servers.
+    ///
+    /// Query targets may override these with their own options.
+    pub db_conn_opts: DbConnOpts<'static>,
+    /// Maximum durations for each phase of running queries on a query target.
+    pub timeouts: Timeouts,
+    /// Protocol used to send queries to the database server.
+    pub query_protocol: QueryProtocol,
+    /// Limits on the amount of data kept from each query target's results.
+    ///
+    /// This does not apply to [`query_rows_stream`], as rows are not kept.
+    ///
+    /// [`query_rows_stream`]: Self::query_rows_stream
+    pub result_limits: ResultLimits,
+    /// Handle to cancel queries that are running through this runner.
+    ///
+    /// This applies to [`query`], [`query_multi`], their variants,
+    /// [`query_rows_stream`], [`ScriptRunner`], and [`TransactionRunner`].
+    /// Queries are killed over a
+    /// dedicated side connection to the query target, which is opened outside
+    /// the pool, so it does not wait for a connection in a full pool.
+    ///
+    /// Queries that exceed the [`Timeouts::query`] timeout are killed in the
+    /// same way, whether or not this is set.
+    ///
+    /// [`query`]: Self::query
+    /// [`query_multi`]: Self::query_multi
+    /// [`query_rows_stream`]: Self::query_rows_stream
+    /// [`ScriptRunner`]: crate::ScriptRunner
+    /// [`TransactionRunner`]: crate::TransactionRunner
+    pub cancel_handle: Option<CancelHandle>,
+    /// Runs queries inside a transaction that is always rolled back.
+    ///
+    /// This applies to [`query`], [`query_multi`], their variants,
+    /// [`query_rows_stream`], [`ScriptRunner`], and [`TransactionRunner`].
+    /// Streams, scripts, and transactions are run in a single transaction per
+    /// query target, and do not select a before-image.
+    ///
+    /// [`exec_multi`] and its variants get their own connections, so they
+    /// cannot honor this, and return [`Error::DryRunUnsupported`] without
+    /// running their queries when this is set.
+    ///
+    /// Queries and scripts with statements that commit or end the transaction,
+    /// such as DDL, `COMMIT`, or `LOCK TABLES`, are not run, and return
+    /// [`Error::DryRunUnsupported`]. [`TransactionRunner`] queries are not
+    /// checked, and must not run such statements. Statements run by stored
+    /// procedures are not checked either.
+    ///
+    /// [`query`]: Self::query
+    /// [`query_multi`]: Self::query_multi
+    /// [`query_rows_stream`]: Self::query_rows_stream
+    /// [`ScriptRunner`]: crate::ScriptRunner
+    /// [`TransactionRunner`]: crate::TransactionRunner
+    /// [`exec_multi`]: Self::exec_multi
+    pub dry_run: Option<DryRun<'static>>,
+    /// Retry policies for opening SSH sessions and tunnels, getting database
+    /// connections, and executing queries.
+    ///
+    /// Query retries apply to [`query`], [`query_multi`], and their variants,
+    /// and to [`query_rows_stream`] until the first item is streamed, but not
+    /// to [`exec_multi`], as the queries may not be idempotent.
+    ///
+    /// [`query`]: Self::query
+    /// [`query_multi`]: Self::query_multi
+    /// [`query_rows_stream`]: Self::query_rows_stream
+    /// [`exec_multi`]: Self::exec_multi
+    pub retry_policies: RetryPolicies,
 }
 
 impl QueryRunner {
+    /// Server error code when the SQL text cannot be parsed
+    /// (`ER_PARSE_ERROR`), which is also returned when preparing multiple
+    /// statements.
+    const ER_PARSE_ERROR: u16 = 1064;
+    /// Server error code when the statement cannot be prepared
+    /// (`ER_UNSUPPORTED_PS`).
+    const ER_UNSUPPORTED_PS: u16 = 1295;
+    /// Maximum number of rows buffered ahead of the caller when streaming
+    /// rows.
+    pub const ROW_STREAM_BUFFER_SIZE: usize = 64;
     /// Maximum number of tunnels per SSH connection.
     pub const SSH_CONCURRENT_LIMIT_DEFAULT: usize = 20;
+    /// Number of times to try to reopen an SSH session that is lost mid-run.
+    pub const SSH_RECONNECT_ATTEMPTS_DEFAULT: u32 = 3;
     /// Maximum number of tunnels per SSH connection.
     pub const TUNNELS_PER_SSH_CONNECTION_DEFAULT: usize = 3;
 
@@ -36,71 +150,371 @@ impl QueryRunner {
             sql_over_ssh: SqlOverSsh,
             ssh_concurrent_limit,
             tunnels_per_ssh_connection,
+            ssh_reconnect_attempts: Self::SSH_RECONNECT_ATTEMPTS_DEFAULT,
+            db_conn_opts: DbConnOpts::default(),
+            timeouts: Timeouts::default(),
+            query_protocol: QueryProtocol::default(),
+            result_limits: ResultLimits::default(),
+            cancel_handle: None,
+            dry_run: None,
+            retry_policies: RetryPolicies::default(),
         }
     }
 
-    /// Queries a database over an SSH connection.
+    /// Queries a database through the given connection route.
+    ///
+    /// If the query target has its own connection route, that is used instead.
     pub async fn query<T>(
         &self,
-        jump_host_address: &HostAddress<'_>,
-        jump_host_auth_params: &JumpHostAuthParams<'_>,
+        connection_route: &ConnectionRoute<'_>,
         query_target: &QueryTarget<'_>,
         sql_text: &str,
     ) -> Result<QueryResult<T>, Error>
     where
         T: FromRow + Send + 'static,
     {
-        let db_tunnel = {
-            let jump_host_address = jump_host_address.clone();
-            let jump_host_auth_params = jump_host_auth_params.clone();
-            let target_socket = HostSocketParams {
-                address: query_target.db_address.clone(),
-                port: 3306,
-            };
-            let ssh_params =
-                SshTunnelParams::new(jump_host_address, jump_host_auth_params, target_socket);
-            SshJumper::open_tunnel(&ssh_params).await?
+        self.query_with_params(connection_route, query_target, sql_text, QueryParams::Empty)
+            .await
+    }
+
+    /// Queries a database through the given connection route, binding the
+    /// parameters to the prepared statement.
+    ///
+    /// If the query target has its own connection route, that is used instead.
+    pub async fn query_with_params<T>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+    ) -> Result<QueryResult<T>, Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let connection_route = query_target
+            .connection_route
+            .as_ref()
+            .unwrap_or(connection_route);
+        let query_targets = [query_target];
+        let target_deadline = TargetDeadline::start(self.timeouts.target);
+        TargetDeadline::run(target_deadline, async {
+            let route_map = self.route_prepare(connection_route, &query_targets).await?;
+            let db_socket = route_map.db_socket(query_target)?;
+
+            self.sql_over_ssh
+                .exec(
+                    db_socket,
+                    query_target.db_schema_cred.clone(),
+                    self.db_conn_opts(query_target),
+                    |pool: mysql_async::Pool| async {
+                        let result = self
+                            .query_run(&pool, query_target, sql_text, query_params)
+                            .await;
+                        (pool, result)
+                    },
+                )
+                .await
+                .map_err(|error| route_map.tunnel_error_take(query_target).unwrap_or(error))
+        })
+        .await
+    }
+
+    /// Queries a database through the given connection route, and streams the
+    /// rows of each result set as they are received.
+    ///
+    /// Rows are read from the server only as fast as the stream is polled, with
+    /// up to [`ROW_STREAM_BUFFER_SIZE`] rows buffered ahead of the caller. The
+    /// end of each result set is marked by [`RowStreamItem::ResultSetEnd`].
+    ///
+    /// If an error occurs, it is the last item in the stream. The [`query`]
+    /// timeout does not apply, as the duration depends on how fast the stream
+    /// is consumed. The [`target`] timeout does apply to the whole stream, so
+    /// it should allow for the time taken to consume it. If it elapses, or the
+    /// query is cancelled through the [`cancel_handle`], the query is killed.
+    ///
+    /// Failed attempts are retried under the [`query`][`RetryPolicies::query`]
+    /// retry policy until the first item is streamed, after which an error
+    /// ends the stream.
+    ///
+    /// When [`dry_run`] is set, the query is run inside a transaction that is
+    /// rolled back after the last row is sent, and no before-image is
+    /// selected.
+    ///
+    /// [`ROW_STREAM_BUFFER_SIZE`]: Self::ROW_STREAM_BUFFER_SIZE
+    /// [`query`]: Timeouts::query
+    /// [`target`]: Timeouts::target
+    /// [`cancel_handle`]: Self::cancel_handle
+    /// [`dry_run`]: Self::dry_run
+    pub fn query_rows_stream<'f, T>(
+        &'f self,
+        connection_route: &'f ConnectionRoute<'f>,
+        query_target: &'f QueryTarget<'f>,
+        sql_text: &'f str,
+        query_params: QueryParams,
+    ) -> impl Stream<Item = Result<RowStreamItem<T>, Error>> + 'f
+    where
+        T: FromRow + Send + 'static,
+    {
+        let (mut row_tx, row_rx) = mpsc::channel(Self::ROW_STREAM_BUFFER_SIZE);
+        let rows_produce = async move {
+            let result = self
+                .query_rows_produce(
+                    connection_route,
+                    query_target,
+                    sql_text,
+                    query_params,
+                    &mut row_tx,
+                )
+                .await;
+            if let Err(error) = result {
+                // The receiver may have been dropped, in which case the error is not needed.
+                let _ = row_tx.send(Err(error)).await;
+            }
         };
 
-        self.sql_over_ssh
-            .exec(
-                db_tunnel,
-                query_target.db_schema_cred.clone(),
-                |pool: mysql_async::Pool| async {
-                    let result = Self::query_run(&pool, query_target, sql_text).await;
-                    (pool, result)
-                },
-            )
+        // The producer never yields items; it is polled alongside the receiver so that
+        // rows are sent while the stream is consumed.
+        stream::select(
+            row_rx,
+            stream::once(rows_produce).filter_map(|()| future::ready(None)),
+        )
+    }
+
+    /// Runs the query through the connection route, and sends each row to
+    /// `row_tx`.
+    ///
+    /// If the [`target`] timeout elapses while the query is running, the query
+    /// is killed on the server.
+    ///
+    /// [`target`]: Timeouts::target
+    async fn query_rows_produce<T>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+        row_tx: &mut RowSender<T>,
+    ) -> Result<(), Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let target_deadline = TargetDeadline::start(self.timeouts.target);
+        let connection_route = query_target
+            .connection_route
+            .as_ref()
+            .unwrap_or(connection_route);
+        let query_targets = [query_target];
+        let route_map = TargetDeadline::run(
+            target_deadline,
+            self.route_prepare(connection_route, &query_targets),
+        )
+        .await?;
+        let db_socket = route_map.db_socket(query_target)?;
+
+        let mut row_stream_progress = RowStreamProgress::default();
+        let rows_send = self.sql_over_ssh.exec(
+            db_socket,
+            query_target.db_schema_cred.clone(),
+            self.db_conn_opts(query_target),
+            |pool: mysql_async::Pool| async {
+                let result = self
+                    .query_rows_run(
+                        &pool,
+                        query_target,
+                        sql_text,
+                        query_params,
+                        row_tx,
+                        &mut row_stream_progress,
+                    )
+                    .await;
+                (pool, result)
+            },
+        );
+        let result = TargetDeadline::run(target_deadline, rows_send)
             .await
+            .map_err(|error| route_map.tunnel_error_take(query_target).unwrap_or(error));
+
+        if let Some((conn_opts, connection_id)) = row_stream_progress.query_running {
+            // The query keeps running on the server unless it is killed. Failing to kill it
+            // does not change the outcome for the caller, so the error is discarded.
+            let _ = Self::query_kill(&conn_opts, connection_id, self.timeouts).await;
+        }
+
+        result
+    }
+
+    /// Runs the query on a connection from the pool, and sends each row to
+    /// `row_tx`.
+    ///
+    /// Failed attempts are retried under the [`query`] retry policy, but only
+    /// until the first item has been sent.
+    ///
+    /// [`query`]: RetryPolicies::query
+    async fn query_rows_run<T>(
+        &self,
+        pool: &mysql_async::Pool,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+        row_tx: &mut RowSender<T>,
+        row_stream_progress: &mut RowStreamProgress,
+    ) -> Result<(), Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let retry_policy = &self.retry_policies.query;
+        let mut attempt = 1;
+        loop {
+            let result = self
+                .query_rows_attempt(
+                    pool,
+                    query_target,
+                    sql_text,
+                    query_params.clone(),
+                    row_tx,
+                    row_stream_progress,
+                )
+                .await;
+            match result {
+                Err(error)
+                    if !row_stream_progress.items_sent
+                        && retry_policy.retry_should(attempt, &error) =>
+                {
+                    retry::backoff_sleep(retry_policy, attempt).await;
+                    attempt += 1;
+                }
+                result => break retry::attempts_record(result, attempt),
+            }
+        }
+    }
+
+    /// Makes one attempt to run the query on a connection from the pool, and
+    /// sends each row to `row_tx`.
+    ///
+    /// `row_stream_progress` records the connection while the query runs, and
+    /// whether any item has been sent.
+    async fn query_rows_attempt<T>(
+        &self,
+        pool: &mysql_async::Pool,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+        row_tx: &mut RowSender<T>,
+        row_stream_progress: &mut RowStreamProgress,
+    ) -> Result<(), Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        if self.dry_run.is_some() {
+            Self::dry_run_check(sql_text)?;
+        }
+
+        let mut conn = self.conn_acquire(pool).await?;
+        let _query_registration = self.query_register(query_target, &conn)?;
+        let query_running = (conn.opts().clone(), conn.id());
+        let items_sent = &mut row_stream_progress.items_sent;
+
+        let result = match self.dry_run {
+            Some(_) => {
+                let mut transaction = conn
+                    .start_transaction(TxOpts::default())
+                    .await
+                    .map_err(Error::TransactionBegin)?;
+                row_stream_progress.query_running = Some(query_running);
+                let result = Self::query_rows_send(
+                    &mut transaction,
+                    sql_text,
+                    query_params,
+                    self.query_protocol,
+                    row_tx,
+                    items_sent,
+                )
+                .await;
+                let rollback = transaction
+                    .rollback()
+                    .await
+                    .map_err(Error::TransactionRollback);
+
+                result.and(rollback)
+            }
+            None => {
+                row_stream_progress.query_running = Some(query_running);
+                Self::query_rows_send(
+                    &mut conn,
+                    sql_text,
+                    query_params,
+                    self.query_protocol,
+                    row_tx,
+                    items_sent,
+                )
+                .await
+            }
+        };
+        row_stream_progress.query_running = None;
+
+        result.map_err(|error| self.error_cancelled_map(error))
     }
 
     /// Queries multiple query targets with the same query.
+    ///
+    /// Query targets are grouped by their connection route, falling back to
+    /// `connection_route` for targets without one, and each group is chunked
+    /// by [`tunnels_per_ssh_connection`].
+    ///
+    /// [`tunnels_per_ssh_connection`]: Self::tunnels_per_ssh_connection
     pub async fn query_multi<T>(
         &self,
-        jump_host_address: &HostAddress<'_>,
-        jump_host_auth_params: &JumpHostAuthParams<'_>,
+        connection_route: &ConnectionRoute<'_>,
         query_targets: &[QueryTarget<'_>],
         sql_text: &str,
     ) -> (Vec<QueryResult<T>>, Vec<QueryError>)
     where
         T: FromRow + Send + 'static,
     {
-        stream::iter(query_targets.chunks(self.tunnels_per_ssh_connection))
-            .map(|query_targets_chunk| async move {
-                let ssh_tunnel_map = SshTunnelManager::prepare_tunnels(
-                    jump_host_address,
-                    jump_host_auth_params,
-                    query_targets_chunk,
+        self.query_multi_with_params(connection_route, query_targets, sql_text, |_| {
+            QueryParams::Empty
+        })
+        .await
+    }
+
+    /// Queries multiple query targets with the same query, binding each
+    /// target's parameters to the prepared statement.
+    ///
+    /// `query_params_fn` returns the parameters for each query target, so the
+    /// same query may be run with per-target values. Query targets are grouped
+    /// and chunked in the same way as [`query_multi`].
+    ///
+    /// [`query_multi`]: Self::query_multi
+    pub async fn query_multi_with_params<T, QueryParamsFn>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &[QueryTarget<'_>],
+        sql_text: &str,
+        query_params_fn: QueryParamsFn,
+    ) -> (Vec<QueryResult<T>>, Vec<QueryError>)
+    where
+        T: FromRow + Send + 'static,
+        QueryParamsFn: Fn(&QueryTarget<'_>) -> QueryParams,
+    {
+        let query_params_fn = &query_params_fn;
+        let route_groups = ConnectionRouteManager::group_by_route(connection_route, query_targets);
+        stream::iter(self.route_chunks(&route_groups))
+            .map(|(connection_route, query_targets_chunk)| async move {
+                let target_deadline = TargetDeadline::start(self.timeouts.target);
+                let route_map = TargetDeadline::run(
+                    target_deadline,
+                    self.route_prepare(connection_route, query_targets_chunk),
                 )
                 .await;
 
-                match ssh_tunnel_map {
-                    Ok(ssh_tunnel_map) => {
-                        self.query_over_tunnels(
-                            jump_host_address,
+                match route_map {
+                    Ok(route_map) => {
+                        self.query_over_route(
                             query_targets_chunk,
                             sql_text,
-                            ssh_tunnel_map,
+                            query_params_fn,
+                            route_map,
+                            target_deadline,
                         )
                         .await
                     }
@@ -108,9 +522,11 @@ impl QueryRunner {
                         let mut error = Some(e);
                         let errors = query_targets_chunk
                             .iter()
-                            .map(|query_target| QueryError {
-                                name: query_target.name.clone().into_owned(),
-                                error: error.take().unwrap_or(Error::SshConnInit),
+                            .map(|query_target| {
+                                QueryError::new(
+                                    query_target,
+                                    error.take().unwrap_or(Error::SshConnInit),
+                                )
                             })
                             .collect::<Vec<QueryError>>();
                         (vec![], errors)
@@ -134,11 +550,95 @@ impl QueryRunner {
             .await
     }
 
-    /// Queries multiple query targets with the same query.
+    /// Queries multiple query targets with the same query, yielding each
+    /// target's result as soon as it is ready.
+    ///
+    /// Query targets are grouped and chunked in the same way as
+    /// [`query_multi`], and up to [`ssh_concurrent_limit`] chunks are run at a
+    /// time, but results are yielded in the order they complete.
+    ///
+    /// [`query_multi`]: Self::query_multi
+    /// [`ssh_concurrent_limit`]: Self::ssh_concurrent_limit
+    pub fn query_multi_stream<'f, T>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        sql_text: &'f str,
+    ) -> impl Stream<Item = Result<QueryResult<T>, QueryError>> + 'f
+    where
+        T: FromRow + Send + 'static,
+    {
+        self.query_multi_with_params_stream(connection_route, query_targets, sql_text, |_| {
+            QueryParams::Empty
+        })
+    }
+
+    /// Queries multiple query targets with the same query, binding each
+    /// target's parameters, and yielding each target's result as soon as it is
+    /// ready.
+    ///
+    /// See [`query_multi_with_params`] and [`query_multi_stream`].
+    ///
+    /// [`query_multi_with_params`]: Self::query_multi_with_params
+    /// [`query_multi_stream`]: Self::query_multi_stream
+    pub fn query_multi_with_params_stream<'f, T, QueryParamsFn>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        sql_text: &'f str,
+        query_params_fn: QueryParamsFn,
+    ) -> impl Stream<Item = Result<QueryResult<T>, QueryError>> + 'f
+    where
+        T: FromRow + Send + 'static,
+        QueryParamsFn: Fn(&QueryTarget<'_>) -> QueryParams + 'f,
+    {
+        let query_params_fn = Arc::new(query_params_fn);
+        stream::iter(self.route_chunks_owned(connection_route, query_targets))
+            .map(move |(connection_route, query_targets_chunk)| {
+                let query_params_fn = Arc::clone(&query_params_fn);
+                self.chunk_stream(
+                    connection_route,
+                    query_targets_chunk,
+                    move |route_map, query_target, target_deadline| {
+                        let query_params = query_params_fn(query_target);
+                        async move {
+                            self.query_target_run(
+                                &route_map,
+                                query_target,
+                                sql_text,
+                                query_params,
+                                target_deadline,
+                            )
+                            .await
+                        }
+                    },
+                    |query_target, error| Err(QueryError::new(query_target, error)),
+                )
+            })
+            .flatten_unordered(self.ssh_concurrent_limit)
+    }
+
+    /// Runs queries against multiple query targets.
+    ///
+    /// Query targets are grouped by their connection route, falling back to
+    /// `connection_route` for targets without one, and each group is chunked
+    /// by [`tunnels_per_ssh_connection`].
+    ///
+    /// The [`db_connect`] and [`query`] timeouts do not apply to `queries`, as
+    /// it gets its own connections, but the [`target`] timeout does.
+    ///
+    /// `queries` cannot be run as a dry run, so if [`dry_run`] is set, it is
+    /// not run, and [`Error::DryRunUnsupported`] is returned for each query
+    /// target.
+    ///
+    /// [`tunnels_per_ssh_connection`]: Self::tunnels_per_ssh_connection
+    /// [`db_connect`]: Timeouts::db_connect
+    /// [`query`]: Timeouts::query
+    /// [`target`]: Timeouts::target
+    /// [`dry_run`]: Self::dry_run
     pub async fn exec_multi<'f, Queries>(
         &'f self,
-        jump_host_address: HostAddress<'f>,
-        jump_host_auth_params: JumpHostAuthParams<'f>,
+        connection_route: &ConnectionRoute<'_>,
         query_targets: &'f [QueryTarget<'f>],
         queries: Queries,
     ) -> (
@@ -149,31 +649,75 @@ impl QueryRunner {
         Queries: FnWithPool<'f> + Copy,
         <Queries as FnWithPool<'f>>::Error: From<Error>,
     {
-        let jump_host_address = &jump_host_address;
-        let jump_host_auth_params = &jump_host_auth_params;
-        stream::iter(query_targets.chunks(self.tunnels_per_ssh_connection))
-            .map(|query_targets_chunk| async move {
-                let ssh_tunnel_map = SshTunnelManager::prepare_tunnels(
-                    jump_host_address,
-                    jump_host_auth_params,
-                    query_targets_chunk,
+        if self.dry_run.is_some() {
+            let exec_errors = query_targets
+                .iter()
+                .map(|query_target| {
+                    (
+                        query_target,
+                        <Queries as FnWithPool<'f>>::Error::from(Error::DryRunUnsupported),
+                    )
+                })
+                .collect();
+            return (Vec::new(), exec_errors);
+        }
+
+        self.exec_multi_with_target(connection_route, query_targets, move |_query_target| {
+            queries
+        })
+        .await
+    }
+
+    /// Runs queries against multiple query targets, where the queries for
+    /// each query target are returned by `queries_fn`.
+    ///
+    /// This behaves the same as [`exec_multi`], but lets the queries know
+    /// which query target they run on, and runs them even if [`dry_run`] is
+    /// set, so callers must honor it themselves.
+    ///
+    /// [`dry_run`]: Self::dry_run
+    /// [`exec_multi`]: Self::exec_multi
+    pub(crate) async fn exec_multi_with_target<'f, QueriesFn, Queries>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        queries_fn: QueriesFn,
+    ) -> (
+        Vec<(&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Output)>,
+        Vec<(&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Error)>,
+    )
+    where
+        QueriesFn: Fn(&'f QueryTarget<'f>) -> Queries + Copy,
+        Queries: FnWithPool<'f>,
+        <Queries as FnWithPool<'f>>::Error: From<Error>,
+    {
+        let route_groups = ConnectionRouteManager::group_by_route(connection_route, query_targets);
+        stream::iter(self.route_chunks(&route_groups))
+            .map(|(connection_route, query_targets_chunk)| async move {
+                let target_deadline = TargetDeadline::start(self.timeouts.target);
+                let route_map = TargetDeadline::run(
+                    target_deadline,
+                    self.route_prepare(connection_route, query_targets_chunk),
                 )
                 .await;
 
-                match ssh_tunnel_map {
-                    Ok(ssh_tunnel_map) => {
-                        self
-                            .exec_over_tunnels(jump_host_address, query_targets, queries, ssh_tunnel_map)
-                            .await
+                match route_map {
+                    Ok(route_map) => {
+                        self.exec_over_route(
+                            query_targets_chunk,
+                            queries_fn,
+                            route_map,
+                            target_deadline,
+                        )
+                        .await
                     }
                     Err(e) => {
                         let mut error = Some(e);
                         let errors = query_targets_chunk
                             .iter()
                             .map(|query_target| {
-
                                 let error = error.take().unwrap_or(Error::SshConnInit);
-                                (query_target, <Queries as FnWithPool<'f>>::Error::from(error))
+                                (*query_target, <Queries as FnWithPool<'f>>::Error::from(error))
                             })
                             .collect::<Vec<(&QueryTarget<'_>, <Queries as FnWithPool<'f>>::Error)>>();
                         (vec![], errors)
@@ -197,45 +741,114 @@ impl QueryRunner {
             .await
     }
 
-    async fn query_over_tunnels<T>(
+    /// Runs queries against multiple query targets, yielding each target's
+    /// outcome as soon as it is ready.
+    ///
+    /// Query targets are grouped and chunked in the same way as
+    /// [`exec_multi`], and up to [`ssh_concurrent_limit`] chunks are run at a
+    /// time, but outcomes are yielded in the order they complete.
+    ///
+    /// As with [`exec_multi`], if [`dry_run`] is set, `queries` is not run, and
+    /// [`Error::DryRunUnsupported`] is yielded for each query target.
+    ///
+    /// [`exec_multi`]: Self::exec_multi
+    /// [`ssh_concurrent_limit`]: Self::ssh_concurrent_limit
+    /// [`dry_run`]: Self::dry_run
+    pub fn exec_multi_stream<'f, Queries>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        queries: Queries,
+    ) -> impl Stream<Item = ExecOutcome<'f, Queries>> + 'f
+    where
+        Queries: FnWithPool<'f> + Copy + 'f,
+        <Queries as FnWithPool<'f>>::Error: From<Error>,
+    {
+        if self.dry_run.is_some() {
+            let exec_errors = query_targets.iter().map(|query_target| {
+                Err((
+                    query_target,
+                    <Queries as FnWithPool<'f>>::Error::from(Error::DryRunUnsupported),
+                ))
+            });
+            return stream::iter(exec_errors).left_stream();
+        }
+
+        stream::iter(self.route_chunks_owned(connection_route, query_targets))
+            .map(move |(connection_route, query_targets_chunk)| {
+                self.chunk_stream(
+                    connection_route,
+                    query_targets_chunk,
+                    move |route_map, query_target, target_deadline| async move {
+                        self.exec_target_run(&route_map, query_target, queries, target_deadline)
+                            .await
+                    },
+                    |query_target, error| {
+                        Err((
+                            query_target,
+                            <Queries as FnWithPool<'f>>::Error::from(error),
+                        ))
+                    },
+                )
+            })
+            .flatten_unordered(self.ssh_concurrent_limit)
+            .right_stream()
+    }
+
+    /// Returns each route group split into chunks of
+    /// [`tunnels_per_ssh_connection`] query targets.
+    ///
+    /// [`tunnels_per_ssh_connection`]: Self::tunnels_per_ssh_connection
+    pub(crate) fn route_chunks<'g, 'r, 'f>(
         &self,
-        jump_host_address: &HostAddress<'_>,
-        query_targets: &[QueryTarget<'_>],
+        route_groups: &'g [(&'r ConnectionRoute<'r>, Vec<&'f QueryTarget<'f>>)],
+    ) -> impl Iterator<Item = (&'r ConnectionRoute<'r>, &'g [&'f QueryTarget<'f>])> + 'g {
+        let tunnels_per_ssh_connection = self.tunnels_per_ssh_connection;
+        route_groups
+            .iter()
+            .flat_map(move |(connection_route, query_targets)| {
+                query_targets
+                    .chunks(tunnels_per_ssh_connection)
+                    .map(move |query_targets_chunk| (*connection_route, query_targets_chunk))
+            })
+    }
+
+    /// Returns the query target's connection options, falling back to
+    /// [`db_conn_opts`].
+    ///
+    /// [`db_conn_opts`]: Self::db_conn_opts
+    pub(crate) fn db_conn_opts<'f>(
+        &'f self,
+        query_target: &'f QueryTarget<'_>,
+    ) -> &'f DbConnOpts<'f> {
+        query_target
+            .db_conn_opts
+            .as_ref()
+            .unwrap_or(&self.db_conn_opts)
+    }
+
+    async fn query_over_route<T, QueryParamsFn>(
+        &self,
+        query_targets: &[&QueryTarget<'_>],
         sql_text: &str,
-        ssh_tunnel_map: SshTunnelMap<'_>,
+        query_params_fn: &QueryParamsFn,
+        route_map: RouteMap<'_>,
+        target_deadline: Option<TargetDeadline>,
     ) -> (Vec<QueryResult<T>>, Vec<QueryError>)
     where
         T: FromRow + Send + 'static,
+        QueryParamsFn: Fn(&QueryTarget<'_>) -> QueryParams,
     {
-        let qt_name_to_tunnel = &ssh_tunnel_map.qt_name_to_tunnel;
-        let query_results_and_errors = stream::iter(query_targets.iter())
-            .map(|query_target| async move {
-                let db_tunnel = *qt_name_to_tunnel
-                    .get(query_target.name.as_ref())
-                    .ok_or_else(|| {
-                        let error = Error::SshTunnelNotFound {
-                            jump_host_address: jump_host_address.into_static(),
-                            query_target: query_target.clone().into_static(),
-                        };
-                        QueryError {
-                            name: query_target.name.to_string(),
-                            error,
-                        }
-                    })?;
-                self.sql_over_ssh
-                    .exec(
-                        db_tunnel,
-                        query_target.db_schema_cred.clone(),
-                        |pool: mysql_async::Pool| async {
-                            let result = Self::query_run(&pool, query_target, sql_text).await;
-                            (pool, result)
-                        },
-                    )
-                    .await
-                    .map_err(|error| QueryError {
-                        name: query_target.name.to_string(),
-                        error,
-                    })
+        let route_map = &route_map;
+        let query_results_and_errors = stream::iter(query_targets.iter().copied())
+            .map(|query_target| {
+                self.query_target_run(
+                    route_map,
+                    query_target,
+                    sql_text,
+                    query_params_fn(query_target),
+                    target_deadline,
+                )
             })
             .buffered(self.tunnels_per_ssh_connection)
             .fold(
@@ -253,48 +866,535 @@ impl QueryRunner {
         query_results_and_errors
     }
 
-    async fn query_run<T>(
+    /// Runs the query on a connection from the pool.
+    ///
+    /// Getting the connection is bounded by the [`db_connect`] timeout, and
+    /// running the query and fetching its results is bounded by the [`query`]
+    /// timeout.
+    ///
+    /// When `dry_run` is `Some`, the query is run inside a transaction that is
+    /// rolled back, even if the query fails.
+    ///
+    /// [`db_connect`]: Timeouts::db_connect
+    /// [`query`]: Timeouts::query
+    pub(crate) async fn query_run<T>(
+        &self,
         pool: &mysql_async::Pool,
         query_target: &QueryTarget<'_>,
         sql_text: &str,
+        query_params: QueryParams,
     ) -> Result<QueryResult<T>, Error>
     where
         T: FromRow + Send + 'static,
     {
-        match pool.get_conn().await {
-            Ok(mut conn) => {
-                let statement = conn.prep(sql_text).await.map_err(Error::MySqlPrepare);
-                match statement {
-                    Ok(statement) => {
-                        let result = conn
-                            .exec_iter(statement, ())
-                            .await
-                            .map_err(Error::MySqlExecute);
+        let retry_policy = &self.retry_policies.query;
+        let mut attempt = 1;
+        let (result_sets, before_image) = loop {
+            let result = self
+                .query_attempt(pool, query_target, sql_text, query_params.clone())
+                .await;
+            match result {
+                Err(error) if retry_policy.retry_should(attempt, &error) => {
+                    retry::backoff_sleep(retry_policy, attempt).await;
+                    attempt += 1;
+                }
+                result => break retry::attempts_record(result, attempt)?,
+            }
+        };
 
-                        match result {
-                            Ok(mut query_result) => {
-                                Self::query_result_fetch::<T>(
-                                    query_target.name.to_string(),
-                                    &mut query_result,
-                                )
-                                .await
-                            }
-                            Err(e) => Err(e),
-                        }
-                    }
-                    Err(e) => Err(e),
+        Ok(QueryResult {
+            name: query_target.name.to_string(),
+            db_port: query_target.db_port,
+            result_sets,
+            before_image,
+            attempts: attempt,
+        })
+    }
+
+    /// Makes one attempt to run the query on a connection from the pool.
+    async fn query_attempt<T>(
+        &self,
+        pool: &mysql_async::Pool,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+    ) -> Result<DryRunResultSets<T>, Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        if let Some(dry_run) = self.dry_run.as_ref() {
+            Self::dry_run_check(sql_text)?;
+            dry_run
+                .before_image_sql
+                .as_deref()
+                .map(Self::dry_run_check)
+                .transpose()?;
+        }
+
+        let mut conn = self.conn_acquire(pool).await?;
+        let connection_id = conn.id();
+        let conn_opts = conn.opts().clone();
+        let _query_registration = self.query_register(query_target, &conn)?;
+
+        match self.dry_run.as_ref() {
+            Some(dry_run) => {
+                self.query_dry_run(
+                    &mut conn,
+                    &conn_opts,
+                    connection_id,
+                    sql_text,
+                    query_params,
+                    dry_run,
+                )
+                .await
+            }
+            None => {
+                let mut fetch_budget = FetchBudget::new(self.result_limits);
+                self.query_timeout_kill(
+                    &conn_opts,
+                    connection_id,
+                    Self::query_exec(
+                        &mut conn,
+                        sql_text,
+                        query_params,
+                        self.query_protocol,
+                        &mut fetch_budget,
+                    ),
+                )
+                .await
+                .map(|result_sets| (result_sets, None))
+            }
+        }
+        .map_err(|error| self.error_cancelled_map(error))
+    }
+
+    /// Registers the query running on the connection with the
+    /// [`cancel_handle`], so that it is killed when cancelled.
+    ///
+    /// Returns `None` if there is no cancel handle, and
+    /// [`Error::QueryCancelled`] if it is already cancelled.
+    ///
+    /// [`cancel_handle`]: Self::cancel_handle
+    pub(crate) fn query_register(
+        &self,
+        query_target: &QueryTarget<'_>,
+        conn: &Conn,
+    ) -> Result<Option<QueryRegistration>, Error> {
+        self.cancel_handle
+            .as_ref()
+            .map(|cancel_handle| {
+                cancel_handle.register(query_target, conn.id(), conn.opts(), self.timeouts)
+            })
+            .transpose()
+    }
+
+    /// Returns whether the [`cancel_handle`] has been cancelled.
+    ///
+    /// [`cancel_handle`]: Self::cancel_handle
+    pub(crate) fn cancelled(&self) -> bool {
+        self.cancel_handle
+            .as_ref()
+            .map(CancelHandle::is_cancelled)
+            .unwrap_or(false)
+    }
+
+    /// Returns [`Error::QueryCancelled`] in place of `error` if the
+    /// [`cancel_handle`] has been cancelled, as killed queries fail with a
+    /// server error.
+    ///
+    /// [`cancel_handle`]: Self::cancel_handle
+    pub(crate) fn error_cancelled_map(&self, error: Error) -> Error {
+        if self.cancelled() {
+            Error::QueryCancelled
+        } else {
+            error
+        }
+    }
+
+    /// Runs the query bounded by the [`query`] timeout, and kills it on the
+    /// server if it times out.
+    ///
+    /// The query must be running on the connection with `connection_id`,
+    /// which was opened with `conn_opts`.
+    ///
+    /// [`query`]: Timeouts::query
+    pub(crate) async fn query_timeout_kill<Fut, T>(
+        &self,
+        conn_opts: &Opts,
+        connection_id: u32,
+        query: Fut,
+    ) -> Result<T, Error>
+    where
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let result = timeout(self.timeouts.query, query, |duration| Error::QueryTimeout {
+            duration,
+        })
+        .await;
+        if let Err(Error::QueryTimeout { .. }) = result {
+            // The query keeps running on the server unless it is killed. Failing to kill it
+            // does not change the outcome for the caller, so the error is discarded.
+            let _ = Self::query_kill(conn_opts, connection_id, self.timeouts).await;
+        }
+
+        result.map_err(|error| self.error_cancelled_map(error))
+    }
+
+    /// Returns [`Error::DryRunUnsupported`] if any statement in the SQL text
+    /// commits or ends the transaction, so it cannot be run as a dry run.
+    ///
+    /// The SQL text is split on `;`, so multiple statements sent with the text
+    /// protocol are each checked.
+    pub(crate) fn dry_run_check(sql_text: &str) -> Result<(), Error> {
+        let ends_transaction = SqlScript::parse(sql_text)
+            .statements
+            .iter()
+            .any(SqlStatement::ends_transaction);
+        if ends_transaction {
+            Err(Error::DryRunUnsupported)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Runs the query inside a transaction that is always rolled back, and
+    /// returns its result sets and the before-image.
+    ///
+    /// The before-image and the query each have their own fetch budget, so the
+    /// before-image does not use up the rows allowed for the query. If they
+    /// time out, the query is killed before the transaction is rolled back, as
+    /// the rollback first reads the rest of the pending result.
+    ///
+    /// If this future is dropped before the transaction is rolled back, the
+    /// connection is rolled back when it is returned to the pool.
+    async fn query_dry_run<T>(
+        &self,
+        conn: &mut Conn,
+        conn_opts: &Opts,
+        connection_id: u32,
+        sql_text: &str,
+        query_params: QueryParams,
+        dry_run: &DryRun<'_>,
+    ) -> Result<DryRunResultSets<T>, Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let mut transaction = conn
+            .start_transaction(TxOpts::default())
+            .await
+            .map_err(Error::TransactionBegin)?;
+
+        let query_exec = async {
+            let before_image = match dry_run.before_image_sql.as_deref() {
+                Some(before_image_sql) => {
+                    let mut fetch_budget = FetchBudget::new(self.result_limits);
+                    Some(
+                        Self::query_exec(
+                            &mut transaction,
+                            before_image_sql,
+                            QueryParams::Empty,
+                            self.query_protocol,
+                            &mut fetch_budget,
+                        )
+                        .await?,
+                    )
                 }
+                None => None,
+            };
+            let mut fetch_budget = FetchBudget::new(self.result_limits);
+            let result_sets = Self::query_exec(
+                &mut transaction,
+                sql_text,
+                query_params,
+                self.query_protocol,
+                &mut fetch_budget,
+            )
+            .await?;
+
+            Ok((result_sets, before_image))
+        };
+        let result = self
+            .query_timeout_kill(conn_opts, connection_id, query_exec)
+            .await;
+
+        let rollback = timeout(
+            self.timeouts.query,
+            transaction.rollback().map_err(Error::TransactionRollback),
+            |duration| Error::QueryTimeout { duration },
+        )
+        .await;
+
+        let result_sets = result?;
+        rollback?;
+        Ok(result_sets)
+    }
+
+    /// Opens the SSH sessions and tunnels for the query targets, retrying
+    /// under the [`ssh_setup`] retry policy.
+    ///
+    /// [`ssh_setup`]: RetryPolicies::ssh_setup
+    pub(crate) async fn route_prepare<'qt>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &[&'qt QueryTarget<'qt>],
+    ) -> Result<RouteMap<'qt>, Error> {
+        retry(&self.retry_policies.ssh_setup, || {
+            ConnectionRouteManager::prepare(
+                connection_route,
+                query_targets,
+                self.ssh_reconnect_attempts,
+                self.timeouts,
+            )
+        })
+        .await
+    }
+
+    /// Returns a connection from the pool, retrying under the [`db_connect`]
+    /// retry policy.
+    ///
+    /// [`db_connect`]: RetryPolicies::db_connect
+    pub(crate) async fn conn_acquire(&self, pool: &mysql_async::Pool) -> Result<Conn, Error> {
+        retry(&self.retry_policies.db_connect, || {
+            Self::conn_get(pool, self.timeouts)
+        })
+        .await
+    }
+
+    /// Returns a connection from the pool, bounded by the [`db_connect`]
+    /// timeout.
+    ///
+    /// [`db_connect`]: Timeouts::db_connect
+    pub(crate) async fn conn_get(
+        pool: &mysql_async::Pool,
+        timeouts: Timeouts,
+    ) -> Result<Conn, Error> {
+        timeout(
+            timeouts.db_connect,
+            pool.get_conn().map_err(Error::MySqlConnectionRetrieve),
+            |duration| Error::DbConnectTimeout { duration },
+        )
+        .await
+    }
+
+    /// Kills the query running on the connection, using a dedicated side
+    /// connection opened with `conn_opts`.
+    ///
+    /// The side connection is not taken from the connection's pool, so the
+    /// query can be killed even when every connection in the pool is in use.
+    pub(crate) async fn query_kill(
+        conn_opts: &Opts,
+        connection_id: u32,
+        timeouts: Timeouts,
+    ) -> Result<(), Error> {
+        let mut conn = timeout(
+            timeouts.db_connect,
+            Conn::new(conn_opts.clone()).map_err(Error::MySqlConnectionRetrieve),
+            |duration| Error::DbConnectTimeout { duration },
+        )
+        .await?;
+        let result = conn
+            .query_drop(format!("KILL QUERY {}", connection_id))
+            .await
+            .map_err(Error::QueryKill);
+
+        // The side connection is only used for the kill, so failing to cleanly
+        // disconnect it is not actionable, and the error is discarded.
+        let _ = conn.disconnect().await;
+        result
+    }
+
+    /// Runs the query on the connection with the given protocol, and returns
+    /// its result sets.
+    pub(crate) async fn query_exec<T, Q>(
+        conn: &mut Q,
+        sql_text: &str,
+        query_params: QueryParams,
+        query_protocol: QueryProtocol,
+        fetch_budget: &mut FetchBudget,
+    ) -> Result<Vec<ResultSet<T>>, Error>
+    where
+        T: FromRow + Send + 'static,
+        Q: Queryable,
+    {
+        match Self::statement_prepare(conn, sql_text, &query_params, query_protocol).await? {
+            Some(statement) => {
+                let mut query_result = conn
+                    .exec_iter(statement, query_params)
+                    .await
+                    .map_err(Error::MySqlExecute)?;
+
+                Self::query_result_fetch::<T, _>(&mut query_result, fetch_budget).await
             }
-            Err(e) => Err(Error::MySqlConnectionRetrieve(e)),
+            None => Self::query_exec_text(conn, sql_text, query_params, fetch_budget).await,
         }
     }
 
-    async fn query_result_fetch<T>(
-        query_target_name: String,
-        query_result: &mut mysql_async::QueryResult<'_, '_, BinaryProtocol>,
-    ) -> Result<QueryResult<T>, Error>
+    /// Prepares the statement for the given protocol.
+    ///
+    /// Returns `None` if the query should be run with the text protocol.
+    async fn statement_prepare<Q>(
+        conn: &mut Q,
+        sql_text: &str,
+        query_params: &QueryParams,
+        query_protocol: QueryProtocol,
+    ) -> Result<Option<Statement>, Error>
+    where
+        Q: Queryable,
+    {
+        match query_protocol {
+            QueryProtocol::Binary => conn
+                .prep(sql_text)
+                .await
+                .map(Some)
+                .map_err(Error::MySqlPrepare),
+            QueryProtocol::Text => Ok(None),
+            QueryProtocol::Auto => match conn.prep(sql_text).await {
+                Ok(statement) => Ok(Some(statement)),
+                Err(mysql_async::Error::Server(server_error))
+                    if query_params.is_empty() && Self::text_fallback(&server_error, sql_text) =>
+                {
+                    Ok(None)
+                }
+                Err(e) => Err(Error::MySqlPrepare(e)),
+            },
+        }
+    }
+
+    /// Returns whether a statement that the server refused to prepare can be
+    /// run with the text protocol instead.
+    ///
+    /// The server refuses to prepare some admin statements, and multiple
+    /// statements, which are reported as a parse error. Other errors, such as
+    /// a parse error in a single statement or a missing table, would fail the
+    /// same way as text, so they are returned as is.
+    fn text_fallback(server_error: &ServerError, sql_text: &str) -> bool {
+        match server_error.code {
+            Self::ER_UNSUPPORTED_PS => true,
+            Self::ER_PARSE_ERROR => SqlScript::parse(sql_text).statements.len() > 1,
+            _ => false,
+        }
+    }
+
+    /// Runs the query on the connection with the text protocol.
+    async fn query_exec_text<T, Q>(
+        conn: &mut Q,
+        sql_text: &str,
+        query_params: QueryParams,
+        fetch_budget: &mut FetchBudget,
+    ) -> Result<Vec<ResultSet<T>>, Error>
+    where
+        T: FromRow + Send + 'static,
+        Q: Queryable,
+    {
+        if !query_params.is_empty() {
+            return Err(Error::QueryParamsTextProtocol);
+        }
+
+        let mut query_result = conn
+            .query_iter(sql_text)
+            .await
+            .map_err(Error::MySqlExecute)?;
+
+        Self::query_result_fetch::<T, _>(&mut query_result, fetch_budget).await
+    }
+
+    /// Runs the query on the connection with the given protocol, and sends
+    /// each row to `row_tx`.
+    ///
+    /// `items_sent` is set once any item has been sent.
+    async fn query_rows_send<T, Q>(
+        conn: &mut Q,
+        sql_text: &str,
+        query_params: QueryParams,
+        query_protocol: QueryProtocol,
+        row_tx: &mut RowSender<T>,
+        items_sent: &mut bool,
+    ) -> Result<(), Error>
     where
         T: FromRow + Send + 'static,
+        Q: Queryable,
+    {
+        match Self::statement_prepare(conn, sql_text, &query_params, query_protocol).await? {
+            Some(statement) => {
+                let mut query_result = conn
+                    .exec_iter(statement, query_params)
+                    .await
+                    .map_err(Error::MySqlExecute)?;
+
+                Self::query_result_send(&mut query_result, row_tx, items_sent).await
+            }
+            None => {
+                if !query_params.is_empty() {
+                    return Err(Error::QueryParamsTextProtocol);
+                }
+
+                let mut query_result = conn
+                    .query_iter(sql_text)
+                    .await
+                    .map_err(Error::MySqlExecute)?;
+
+                Self::query_result_send(&mut query_result, row_tx, items_sent).await
+            }
+        }
+    }
+
+    /// Sends each row of each result set to `row_tx`, followed by the result
+    /// set's metadata.
+    ///
+    /// Stops early without error if the receiver has been dropped.
+    /// `items_sent` is set once any item has been sent.
+    async fn query_result_send<T, P>(
+        query_result: &mut mysql_async::QueryResult<'_, '_, P>,
+        row_tx: &mut RowSender<T>,
+        items_sent: &mut bool,
+    ) -> Result<(), Error>
+    where
+        T: FromRow + Send + 'static,
+        P: Protocol,
+    {
+        while !query_result.is_empty() {
+            let columns = Self::columns_capture(query_result);
+            while let Some(row) = query_result
+                .next()
+                .await
+                .map_err(Error::QueryResultSetFetch)?
+            {
+                let row = Self::row_convert::<T>(row)?;
+                if row_tx.send(Ok(RowStreamItem::Row(row))).await.is_err() {
+                    return Ok(());
+                }
+                *items_sent = true;
+            }
+
+            let result_set_end = RowStreamItem::ResultSetEnd {
+                affected_rows: query_result.affected_rows(),
+                warning_count: query_result.warnings(),
+                info: query_result.info().into_owned(),
+                columns,
+            };
+            if row_tx.send(Ok(result_set_end)).await.is_err() {
+                return Ok(());
+            }
+            *items_sent = true;
+        }
+
+        Ok(())
+    }
+
+    /// Fetches each result set, keeping rows until a [`result_limits`] limit
+    /// is reached.
+    ///
+    /// Rows beyond the limits are still read from the server and discarded, so
+    /// that the connection can be used for further queries.
+    ///
+    /// [`result_limits`]: Self::result_limits
+    async fn query_result_fetch<T, P>(
+        query_result: &mut mysql_async::QueryResult<'_, '_, P>,
+        fetch_budget: &mut FetchBudget,
+    ) -> Result<Vec<ResultSet<T>>, Error>
+    where
+        T: FromRow + Send + 'static,
+        P: Protocol,
     {
         // A query result may have multiple result sets.
         // Each set may have a different number of columns.
@@ -304,10 +1404,22 @@ impl QueryRunner {
         // https://github.com/blackbeam/mysql_async/issues/90
         let mut result_sets = Vec::new();
         while !query_result.is_empty() {
-            let values = query_result
-                .collect::<T>()
+            // Columns must be captured before the rows are read, as reading past the last
+            // row moves the query result on to the next result set.
+            let columns = Self::columns_capture(query_result);
+            let mut values = Vec::new();
+            let mut truncated_at = None;
+            while let Some(row) = query_result
+                .next()
                 .await
-                .map_err(Error::QueryResultSetFetch)?;
+                .map_err(Error::QueryResultSetFetch)?
+            {
+                if truncated_at.is_none() && fetch_budget.row_keep(&row, values.len()) {
+                    values.push(Self::row_convert::<T>(row)?);
+                } else {
+                    truncated_at.get_or_insert(values.len());
+                }
+            }
             let affected_rows = query_result.affected_rows();
             let warning_count = query_result.warnings();
             let info = query_result.info().into_owned();
@@ -316,48 +1428,62 @@ impl QueryRunner {
                 affected_rows,
                 info,
                 warning_count,
+                columns,
                 values,
+                truncated_at,
             };
             result_sets.push(result_set);
         }
 
-        Ok(QueryResult {
-            name: query_target_name,
-            result_sets,
+        Ok(result_sets)
+    }
+
+    /// Returns the descriptors of the columns in the current result set.
+    fn columns_capture<P>(query_result: &mysql_async::QueryResult<'_, '_, P>) -> Vec<Column>
+    where
+        P: Protocol,
+    {
+        query_result
+            .columns_ref()
+            .iter()
+            .map(Column::from)
+            .collect()
+    }
+
+    /// Converts the row into `T`.
+    fn row_convert<T>(row: mysql_async::Row) -> Result<T, Error>
+    where
+        T: FromRow,
+    {
+        T::from_row_opt(row).map_err(|FromRowError(row)| {
+            Error::QueryResultSetFetch(mysql_async::Error::Driver(DriverError::FromRow { row }))
         })
     }
 
-    async fn exec_over_tunnels<'f, Queries>(
+    async fn exec_over_route<'f, QueriesFn, Queries>(
         &'f self,
-        jump_host_address: &HostAddress<'f>,
-        query_targets: &'f [QueryTarget<'f>],
-        queries: Queries,
-        ssh_tunnel_map: SshTunnelMap<'f>,
+        query_targets: &[&'f QueryTarget<'f>],
+        queries_fn: QueriesFn,
+        route_map: RouteMap<'f>,
+        target_deadline: Option<TargetDeadline>,
     ) -> (
         Vec<(&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Output)>,
         Vec<(&'f QueryTarget<'f>, <Queries as FnWithPool<'f>>::Error)>,
     )
     where
-        Queries: FnWithPool<'f> + Copy,
+        QueriesFn: Fn(&'f QueryTarget<'f>) -> Queries + Copy,
+        Queries: FnWithPool<'f>,
         <Queries as FnWithPool<'f>>::Error: From<Error>,
     {
-        let qt_name_to_tunnel = &ssh_tunnel_map.qt_name_to_tunnel;
-        stream::iter(query_targets.iter())
-            .map(|query_target| async move {
-                let db_tunnel = *qt_name_to_tunnel
-                    .get(query_target.name.as_ref())
-                    .ok_or_else(|| Error::SshTunnelNotFound {
-                        jump_host_address: jump_host_address.clone().into_static(),
-                        query_target: query_target.clone().into_static(),
-                    })
-                    .map_err(<Queries as FnWithPool<'f>>::Error::from)
-                    .map_err(|exec_error| (query_target, exec_error))?;
-
-                self.sql_over_ssh
-                    .exec(db_tunnel, query_target.db_schema_cred.clone(), queries)
-                    .await
-                    .map(|exec_result| (query_target, exec_result))
-                    .map_err(|exec_error| (query_target, exec_error))
+        let route_map = &route_map;
+        stream::iter(query_targets.iter().copied())
+            .map(|query_target| {
+                self.exec_target_run(
+                    route_map,
+                    query_target,
+                    queries_fn(query_target),
+                    target_deadline,
+                )
             })
             .buffered(self.tunnels_per_ssh_connection)
             .fold(
@@ -372,6 +1498,154 @@ impl QueryRunner {
             )
             .await
     }
+
+    /// Runs the query on the query target through its tunnel in the route
+    /// map.
+    async fn query_target_run<T>(
+        &self,
+        route_map: &RouteMap<'_>,
+        query_target: &QueryTarget<'_>,
+        sql_text: &str,
+        query_params: QueryParams,
+        target_deadline: Option<TargetDeadline>,
+    ) -> Result<QueryResult<T>, QueryError>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let db_socket = route_map
+            .db_socket(query_target)
+            .map_err(|error| QueryError::new(query_target, error))?;
+        let query_result = self.sql_over_ssh.exec(
+            db_socket,
+            query_target.db_schema_cred.clone(),
+            self.db_conn_opts(query_target),
+            |pool: mysql_async::Pool| async {
+                let result = self
+                    .query_run(&pool, query_target, sql_text, query_params)
+                    .await;
+                (pool, result)
+            },
+        );
+        TargetDeadline::run(target_deadline, query_result)
+            .await
+            .map_err(|error| {
+                QueryError::new(
+                    query_target,
+                    route_map.tunnel_error_take(query_target).unwrap_or(error),
+                )
+            })
+    }
+
+    /// Runs `queries` on the query target through its tunnel in the route
+    /// map.
+    async fn exec_target_run<'f, Queries>(
+        &'f self,
+        route_map: &RouteMap<'f>,
+        query_target: &'f QueryTarget<'f>,
+        queries: Queries,
+        target_deadline: Option<TargetDeadline>,
+    ) -> ExecOutcome<'f, Queries>
+    where
+        Queries: FnWithPool<'f>,
+        <Queries as FnWithPool<'f>>::Error: From<Error>,
+    {
+        let db_socket = route_map
+            .db_socket(query_target)
+            .map_err(<Queries as FnWithPool<'f>>::Error::from)
+            .map_err(|exec_error| (query_target, exec_error))?;
+
+        let exec_result = self.sql_over_ssh.exec(
+            db_socket,
+            query_target.db_schema_cred.clone(),
+            self.db_conn_opts(query_target),
+            queries,
+        );
+        TargetDeadline::run(target_deadline, exec_result)
+            .await
+            .map(|exec_result| (query_target, exec_result))
+            .map_err(|exec_error| {
+                let exec_error = route_map
+                    .tunnel_error_take(query_target)
+                    .map(<Queries as FnWithPool<'f>>::Error::from)
+                    .unwrap_or(exec_error);
+                (query_target, exec_error)
+            })
+    }
+
+    /// Returns a stream that prepares the connection route for the chunk, and
+    /// yields the outcome of `target_run` for each query target as soon as it
+    /// completes.
+    ///
+    /// If the connection route cannot be prepared, `route_error` is yielded for
+    /// each query target instead.
+    fn chunk_stream<'f, Outcome, TargetRun, TargetRunFut, RouteError>(
+        &'f self,
+        connection_route: ConnectionRoute<'static>,
+        query_targets_chunk: Vec<&'f QueryTarget<'f>>,
+        target_run: TargetRun,
+        route_error: RouteError,
+    ) -> Pin<Box<impl Stream<Item = Outcome> + 'f>>
+    where
+        Outcome: 'f,
+        TargetRun:
+            Fn(Arc<RouteMap<'f>>, &'f QueryTarget<'f>, Option<TargetDeadline>) -> TargetRunFut + 'f,
+        TargetRunFut: Future<Output = Outcome> + 'f,
+        RouteError: Fn(&'f QueryTarget<'f>, Error) -> Outcome + 'f,
+    {
+        let chunk_stream = stream::once(async move {
+            let target_deadline = TargetDeadline::start(self.timeouts.target);
+            let route_map = TargetDeadline::run(
+                target_deadline,
+                self.route_prepare(&connection_route, &query_targets_chunk),
+            )
+            .await;
+
+            match route_map {
+                Ok(route_map) => {
+                    // Each query target's future holds the route map, so tunnels stay open
+                    // until the last query target in the chunk completes.
+                    let route_map = Arc::new(route_map);
+                    stream::iter(query_targets_chunk)
+                        .map(move |query_target| {
+                            target_run(Arc::clone(&route_map), query_target, target_deadline)
+                        })
+                        .buffer_unordered(self.tunnels_per_ssh_connection)
+                        .left_stream()
+                }
+                Err(e) => {
+                    let mut error = Some(e);
+                    let outcomes = query_targets_chunk
+                        .into_iter()
+                        .map(|query_target| {
+                            route_error(query_target, error.take().unwrap_or(Error::SshConnInit))
+                        })
+                        .collect::<Vec<Outcome>>();
+                    stream::iter(outcomes).right_stream()
+                }
+            }
+        })
+        .flatten();
+
+        Box::pin(chunk_stream)
+    }
+
+    /// Returns each route group split into chunks, with each chunk owning its
+    /// connection route.
+    fn route_chunks_owned<'f>(
+        &self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+    ) -> Vec<(ConnectionRoute<'static>, Vec<&'f QueryTarget<'f>>)> {
+        let route_groups = ConnectionRouteManager::group_by_route(connection_route, query_targets);
+        self.route_chunks(&route_groups)
+            .map(|(connection_route, query_targets_chunk)| {
+                (
+                    connection_route.clone().into_static(),
+                    query_targets_chunk.to_vec(),
+                )
+            })
+            .collect()
+    }
 }
 
 impl Default for QueryRunner {
@@ -382,3 +1656,85 @@ impl Default for QueryRunner {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mysql_async::ServerError;
+    use mysql_async_support_model::{DbConnOpts, Error, SslMode};
+
+    use super::QueryRunner;
+    use crate::test_support::query_target;
+
+    fn server_error(code: u16) -> ServerError {
+        ServerError {
+            code,
+            message: String::new(),
+            state: String::from("HY000"),
+        }
+    }
+
+    #[test]
+    fn text_fallback_when_statement_cannot_be_prepared() {
+        assert!(QueryRunner::text_fallback(
+            &server_error(1295),
+            "LOCK TABLES t WRITE"
+        ));
+    }
+
+    #[test]
+    fn text_fallback_on_parse_error_for_multiple_statements() {
+        assert!(QueryRunner::text_fallback(
+            &server_error(1064),
+            "SELECT 1; SELECT 2;"
+        ));
+    }
+
+    #[test]
+    fn no_text_fallback_on_parse_error_for_single_statement() {
+        assert!(!QueryRunner::text_fallback(&server_error(1064), "SELEC 1;"));
+    }
+
+    #[test]
+    fn no_text_fallback_on_other_server_errors() {
+        // `ER_NO_SUCH_TABLE`
+        assert!(!QueryRunner::text_fallback(
+            &server_error(1146),
+            "SELECT * FROM missing"
+        ));
+    }
+
+    #[test]
+    fn db_conn_opts_query_target_overrides_runner() {
+        let mut query_runner = QueryRunner::new(
+            QueryRunner::SSH_CONCURRENT_LIMIT_DEFAULT,
+            QueryRunner::TUNNELS_PER_SSH_CONNECTION_DEFAULT,
+        );
+        query_runner.db_conn_opts = DbConnOpts::new().with_ssl_mode(SslMode::Required);
+        let query_target = query_target("target");
+        let query_target_override = query_target
+            .clone()
+            .with_db_conn_opts(DbConnOpts::new().with_ssl_mode(SslMode::VerifyIdentity));
+
+        assert_eq!(
+            SslMode::Required,
+            query_runner.db_conn_opts(&query_target).ssl_mode
+        );
+        assert_eq!(
+            SslMode::VerifyIdentity,
+            query_runner.db_conn_opts(&query_target_override).ssl_mode
+        );
+    }
+
+    #[test]
+    fn dry_run_check_rejects_statements_that_end_the_transaction() {
+        assert!(QueryRunner::dry_run_check("UPDATE t SET c = 1").is_ok());
+        assert!(matches!(
+            QueryRunner::dry_run_check("UPDATE t SET c = 1; COMMIT"),
+            Err(Error::DryRunUnsupported)
+        ));
+        assert!(matches!(
+            QueryRunner::dry_run_check("ALTER TABLE t ADD COLUMN c INT"),
+            Err(Error::DryRunUnsupported)
+        ));
+    }
+}
@@ -0,0 +1,298 @@
+use std::{net::TcpStream, os::raw::c_int, sync::Arc};
+
+use async_ssh2_lite::{ssh2::ErrorCode, AsyncChannel};
+use futures::{lock::Mutex, FutureExt};
+use mysql_async_support_model::{Error, JumpHost, RetryPolicy, SshSessionChain, Timeouts};
+use ssh_jumper::model::{Error as SshJumperError, HostAddress, HostSocketParams};
+
+use crate::{retry, timeout::timeout, SshTunnelManager};
+
+/// SSH session chain that is re-established when it is found to be dead.
+///
+/// Channels are opened through the last session in the chain. If opening a
+/// channel times out, or fails with anything other than the server refusing
+/// the channel, the session is treated as lost, and the chain is reopened up to
+/// [`reconnect_attempts`] times, with the default [`RetryPolicy`] backoff
+/// between attempts, before the error is returned.
+///
+/// Only channels opened after the chain is reopened go through the new
+/// sessions. Channels that were open on the lost sessions are not
+/// re-established, so connections forwarded through them fail, and must be
+/// reconnected by the caller. Tunnels opened by [`SshTunnelManager`] open a
+/// channel for each connection, so they keep working for new connections.
+///
+/// [`reconnect_attempts`]: Self::reconnect_attempts
+#[derive(Debug)]
+pub struct ReconnectingSshSession {
+    /// Jump hosts to connect through, in order.
+    jump_hosts: Vec<JumpHost<'static>>,
+    /// Number of times to try to reopen the session chain when it is lost.
+    reconnect_attempts: u32,
+    /// Maximum durations to open sessions and channels.
+    timeouts: Timeouts,
+    /// Currently open session chain.
+    ssh_session_chain: Mutex<Arc<SshSessionChain>>,
+}
+
+impl ReconnectingSshSession {
+    /// libssh2 error code when the server refuses to open a channel, such as
+    /// when the target host refuses the connection.
+    const LIBSSH2_ERROR_CHANNEL_FAILURE: c_int = -21;
+
+    /// Opens an SSH session to each jump host, each through the previous one.
+    ///
+    /// # Parameters
+    ///
+    /// * `jump_hosts`: Jump hosts to connect to, in order.
+    /// * `reconnect_attempts`: Number of times to try to reopen the session
+    ///   chain when it is lost.
+    /// * `timeouts`: Maximum durations to open sessions and channels.
+    pub async fn open(
+        jump_hosts: Vec<JumpHost<'static>>,
+        reconnect_attempts: u32,
+        timeouts: Timeouts,
+    ) -> Result<Self, Error> {
+        let ssh_session_chain = Self::ssh_session_chain_open(&jump_hosts, timeouts).await?;
+
+        Ok(Self {
+            jump_hosts,
+            reconnect_attempts,
+            timeouts,
+            ssh_session_chain: Mutex::new(Arc::new(ssh_session_chain)),
+        })
+    }
+
+    /// Returns the number of times to try to reopen the session chain.
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.reconnect_attempts
+    }
+
+    /// Opens a direct channel to the target host through the last session in
+    /// the chain, reconnecting if the session has been lost.
+    ///
+    /// # Parameters
+    ///
+    /// * `target_socket`: The address of the target host to connect to.
+    pub async fn channel_direct_tcpip(
+        &self,
+        target_socket: &HostSocketParams<'_>,
+    ) -> Result<AsyncChannel<TcpStream>, Error> {
+        let ssh_session_chain = Arc::clone(&*self.ssh_session_chain.lock().await);
+        let channel_open_failure = match self.channel_open(&ssh_session_chain, target_socket).await
+        {
+            Ok(Ok(channel)) => return Ok(channel),
+            Ok(Err(io_error)) => ChannelOpenFailure::Io(io_error),
+            Err(error) => ChannelOpenFailure::TimedOut(error),
+        };
+
+        // The target host may have refused the connection while the session is
+        // still usable, in which case reconnecting will not help.
+        let error_code = ssh_session_chain
+            .last()
+            .last_error()
+            .map(|ssh2_error| ssh2_error.code());
+        if !Self::session_lost(&channel_open_failure, error_code) {
+            return Err(channel_open_failure.into_error());
+        }
+
+        let ssh_session_chain = self.reconnect(&ssh_session_chain).await?;
+        self.channel_open(&ssh_session_chain, target_socket)
+            .await?
+            .map_err(|io_error| {
+                Error::SshJumper(Box::new(SshJumperError::SshTunnelOpenFail(io_error)))
+            })
+    }
+
+    /// Opens a direct channel through the session chain.
+    ///
+    /// The outer `Result` is `Err` when opening the channel times out.
+    async fn channel_open(
+        &self,
+        ssh_session_chain: &SshSessionChain,
+        target_socket: &HostSocketParams<'_>,
+    ) -> Result<Result<AsyncChannel<TcpStream>, std::io::Error>, Error> {
+        let target_host_address = target_socket.address.to_string();
+        let channel_open = ssh_session_chain
+            .last()
+            .channel_direct_tcpip(&target_host_address, target_socket.port, None)
+            .map(Ok);
+
+        timeout(self.timeouts.tunnel_open, channel_open, |duration| {
+            Error::TunnelOpenTimeout { duration }
+        })
+        .await
+    }
+
+    /// Opens the session chain, returning [`Error::SshConnectTimeout`] if it
+    /// takes longer than the SSH connect timeout.
+    async fn ssh_session_chain_open(
+        jump_hosts: &[JumpHost<'_>],
+        timeouts: Timeouts,
+    ) -> Result<SshSessionChain, Error> {
+        timeout(
+            timeouts.ssh_connect,
+            SshTunnelManager::open_ssh_session_chain(jump_hosts),
+            |duration| Error::SshConnectTimeout {
+                jump_host_address: jump_hosts
+                    .last()
+                    .map(|jump_host| jump_host.address.into_static())
+                    .expect("Timeout only elapses when there is at least one jump host."),
+                duration,
+            },
+        )
+        .await
+    }
+
+    /// Replaces the dead session chain with a newly opened one.
+    ///
+    /// If another task has already replaced the dead chain, its replacement is
+    /// returned instead.
+    async fn reconnect(
+        &self,
+        ssh_session_chain_dead: &Arc<SshSessionChain>,
+    ) -> Result<Arc<SshSessionChain>, Error> {
+        let mut ssh_session_chain = self.ssh_session_chain.lock().await;
+        if !Arc::ptr_eq(&*ssh_session_chain, ssh_session_chain_dead) {
+            return Ok(Arc::clone(&*ssh_session_chain));
+        }
+
+        let retry_policy = RetryPolicy::new(self.reconnect_attempts);
+        let mut error = None;
+        for attempt in 1..=self.reconnect_attempts {
+            if attempt > 1 {
+                retry::backoff_sleep(&retry_policy, attempt - 1).await;
+            }
+
+            match Self::ssh_session_chain_open(&self.jump_hosts, self.timeouts).await {
+                Ok(ssh_session_chain_new) => {
+                    *ssh_session_chain = Arc::new(ssh_session_chain_new);
+                    return Ok(Arc::clone(&*ssh_session_chain));
+                }
+                Err(e) => error = Some(Box::new(e)),
+            }
+        }
+
+        Err(Error::SshReconnect {
+            jump_host_address: self.jump_host_address(),
+            attempts: self.reconnect_attempts,
+            error,
+        })
+    }
+
+    /// Returns whether the session is lost after failing to open a channel,
+    /// given the session's last libssh2 error code.
+    ///
+    /// A session that was silently dropped, such as by a bastion restart or an
+    /// idle timeout on the network path, usually shows up as a channel open
+    /// that hangs until it times out, rather than as an error.
+    fn session_lost(
+        channel_open_failure: &ChannelOpenFailure,
+        error_code: Option<ErrorCode>,
+    ) -> bool {
+        match channel_open_failure {
+            ChannelOpenFailure::TimedOut(_) => true,
+            ChannelOpenFailure::Io(_) => !Self::session_alive(error_code),
+        }
+    }
+
+    /// Returns whether the session is still usable after failing to open a
+    /// channel, given the session's last libssh2 error code.
+    ///
+    /// A channel failure is a reply from the server, so the session is alive.
+    /// Any other error, such as a socket error, means the session is lost.
+    fn session_alive(error_code: Option<ErrorCode>) -> bool {
+        matches!(
+            error_code,
+            Some(ErrorCode::Session(Self::LIBSSH2_ERROR_CHANNEL_FAILURE))
+        )
+    }
+
+    /// Returns the address of the last jump host in the chain.
+    fn jump_host_address(&self) -> HostAddress<'static> {
+        self.jump_hosts
+            .last()
+            .map(|jump_host| jump_host.address.into_static())
+            .expect("`ReconnectingSshSession` is only opened with at least one jump host.")
+    }
+}
+
+/// Reason that opening a channel failed.
+#[derive(Debug)]
+enum ChannelOpenFailure {
+    /// Opening the channel timed out.
+    TimedOut(Error),
+    /// The session returned an error.
+    Io(std::io::Error),
+}
+
+impl ChannelOpenFailure {
+    /// Returns the error to return to the caller.
+    fn into_error(self) -> Error {
+        match self {
+            Self::TimedOut(error) => error,
+            Self::Io(io_error) => {
+                Error::SshJumper(Box::new(SshJumperError::SshTunnelOpenFail(io_error)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, time::Duration};
+
+    use async_ssh2_lite::ssh2::ErrorCode;
+    use mysql_async_support_model::Error;
+
+    use super::{ChannelOpenFailure, ReconnectingSshSession};
+
+    #[test]
+    fn session_alive_when_channel_refused() {
+        assert!(ReconnectingSshSession::session_alive(Some(
+            ErrorCode::Session(-21)
+        )));
+    }
+
+    #[test]
+    fn session_lost_on_socket_error() {
+        // `LIBSSH2_ERROR_SOCKET_DISCONNECT` and `LIBSSH2_ERROR_SOCKET_RECV`.
+        assert!(!ReconnectingSshSession::session_alive(Some(
+            ErrorCode::Session(-13)
+        )));
+        assert!(!ReconnectingSshSession::session_alive(Some(
+            ErrorCode::Session(-43)
+        )));
+        assert!(!ReconnectingSshSession::session_alive(None));
+    }
+
+    #[test]
+    fn session_lost_when_channel_open_times_out() {
+        let channel_open_failure = ChannelOpenFailure::TimedOut(Error::TunnelOpenTimeout {
+            duration: Duration::from_secs(30),
+        });
+
+        assert!(ReconnectingSshSession::session_lost(
+            &channel_open_failure,
+            None
+        ));
+        assert!(ReconnectingSshSession::session_lost(
+            &channel_open_failure,
+            Some(ErrorCode::Session(-21))
+        ));
+    }
+
+    #[test]
+    fn session_not_lost_when_channel_refused() {
+        let channel_open_failure =
+            ChannelOpenFailure::Io(io::Error::from(io::ErrorKind::ConnectionRefused));
+
+        assert!(!ReconnectingSshSession::session_lost(
+            &channel_open_failure,
+            Some(ErrorCode::Session(-21))
+        ));
+        assert!(ReconnectingSshSession::session_lost(
+            &channel_open_failure,
+            Some(ErrorCode::Session(-13))
+        ));
+    }
+}
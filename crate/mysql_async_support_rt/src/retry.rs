@@ -0,0 +1,68 @@
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use mysql_async_support_model::{Error, RetryPolicy};
+
+/// Runs the attempt until it succeeds, or fails with an error that the retry
+/// policy does not allow to be retried.
+///
+/// If more than one attempt was made, a failure is returned as
+/// [`Error::RetryExhausted`].
+pub(crate) async fn retry<AttemptFn, Fut, T>(
+    retry_policy: &RetryPolicy,
+    mut attempt_fn: AttemptFn,
+) -> Result<T, Error>
+where
+    AttemptFn: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 1;
+    loop {
+        match attempt_fn().await {
+            Err(error) if retry_policy.retry_should(attempt, &error) => {
+                backoff_sleep(retry_policy, attempt).await;
+                attempt += 1;
+            }
+            result => return attempts_record(result, attempt),
+        }
+    }
+}
+
+/// Waits for the randomized backoff after `attempt` failed.
+pub(crate) async fn backoff_sleep(retry_policy: &RetryPolicy, attempt: u32) {
+    let backoff_half = retry_policy.backoff(attempt) / 2;
+    let jitter = backoff_half.mul_f64(random_fraction());
+    tokio::time::sleep(backoff_half + jitter).await;
+}
+
+/// Wraps the error in [`Error::RetryExhausted`] if more than one attempt was
+/// made.
+pub(crate) fn attempts_record<T>(result: Result<T, Error>, attempts: u32) -> Result<T, Error> {
+    result.map_err(|error| {
+        if attempts > 1 {
+            Error::RetryExhausted {
+                attempts,
+                error: Box::new(error),
+            }
+        } else {
+            error
+        }
+    })
+}
+
+/// Returns a random number in `[0, 1)`.
+///
+/// This is not cryptographically secure, but is enough to spread out retries.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default();
+    hasher.write_u32(nanos);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
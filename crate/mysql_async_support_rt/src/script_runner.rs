@@ -0,0 +1,228 @@
+use futures::TryFutureExt;
+use mysql_async::{
+    prelude::{FromRow, Queryable},
+    Opts, Pool, TxOpts,
+};
+use mysql_async_support_model::{
+    ConnectionRoute, Error, QueryParams, QueryTarget, ScriptError, ScriptErrorPolicy, ScriptResult,
+    SqlScript, SqlStatement, StatementResult,
+};
+
+use crate::{fetch_budget::FetchBudget, timeout::timeout, QueryRunner};
+
+/// Runs SQL scripts on one or more query targets.
+#[derive(Clone)]
+pub struct ScriptRunner {
+    /// Connection settings and limits used to run the script.
+    pub query_runner: QueryRunner,
+    /// What to do when a statement in the script fails.
+    pub script_error_policy: ScriptErrorPolicy,
+}
+
+/// Results and errors of each statement run on a query target.
+type StatementOutcomes<T> = (Vec<StatementResult<T>>, Vec<(usize, Error)>);
+
+impl ScriptRunner {
+    /// Returns a new `ScriptRunner`.
+    pub fn new(query_runner: QueryRunner, script_error_policy: ScriptErrorPolicy) -> Self {
+        Self {
+            query_runner,
+            script_error_policy,
+        }
+    }
+
+    /// Runs the script on multiple query targets.
+    ///
+    /// Statements are run in order on a single connection per query target, so
+    /// session state such as `USE` or temporary tables carries over between
+    /// statements. Query targets are grouped and chunked in the same way as
+    /// [`QueryRunner::exec_multi`].
+    ///
+    /// Statements that cannot be prepared, such as `CREATE PROCEDURE`, need the
+    /// [`query_protocol`] to be [`Text`] or [`Auto`].
+    ///
+    /// When [`dry_run`] is set, the script is run inside a transaction that is
+    /// rolled back. Statements that commit or end that transaction, such as
+    /// DDL like `CREATE TABLE`, `COMMIT`, or `LOCK TABLES`, would commit the
+    /// script's changes, so if the script has any, no statement is run, and
+    /// [`Error::DryRunUnsupported`] is returned for each of them.
+    ///
+    /// [`query_protocol`]: QueryRunner::query_protocol
+    /// [`dry_run`]: QueryRunner::dry_run
+    /// [`Text`]: mysql_async_support_model::QueryProtocol::Text
+    /// [`Auto`]: mysql_async_support_model::QueryProtocol::Auto
+    pub async fn run_multi<'f, T>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        sql_script: &'f SqlScript,
+    ) -> (Vec<ScriptResult<T>>, Vec<ScriptError>)
+    where
+        T: FromRow + Send + 'static,
+    {
+        let (exec_results, exec_errors) = self
+            .query_runner
+            .exec_multi_with_target(connection_route, query_targets, move |query_target| {
+                move |pool: Pool| async move {
+                    let result = self
+                        .statements_run::<T>(&pool, query_target, sql_script)
+                        .await;
+                    (pool, result)
+                }
+            })
+            .await;
+
+        let mut script_errors = exec_errors
+            .into_iter()
+            .map(|(query_target, error)| ScriptError {
+                name: query_target.name.to_string(),
+                line: None,
+                error,
+            })
+            .collect::<Vec<ScriptError>>();
+        let script_results = exec_results
+            .into_iter()
+            .map(|(query_target, (statement_results, statement_errors))| {
+                let name = query_target.name.to_string();
+                script_errors.extend(statement_errors.into_iter().map(|(line, error)| {
+                    ScriptError {
+                        name: name.clone(),
+                        line: Some(line),
+                        error,
+                    }
+                }));
+
+                ScriptResult {
+                    name,
+                    statement_results,
+                }
+            })
+            .collect::<Vec<ScriptResult<T>>>();
+
+        (script_results, script_errors)
+    }
+
+    /// Runs each statement in order on a connection from the pool.
+    ///
+    /// When [`dry_run`] is set, the statements are run inside a transaction
+    /// that is rolled back, and the rollback is bounded by the [`query`]
+    /// timeout. If any statement would end the transaction, no statement is
+    /// run.
+    ///
+    /// The connection is registered with the [`cancel_handle`], and a
+    /// statement that times out is killed before the next statement is run.
+    ///
+    /// Returns `Err` if the connection could not be retrieved.
+    ///
+    /// [`dry_run`]: QueryRunner::dry_run
+    /// [`query`]: mysql_async_support_model::Timeouts::query
+    /// [`cancel_handle`]: QueryRunner::cancel_handle
+    async fn statements_run<T>(
+        &self,
+        pool: &Pool,
+        query_target: &QueryTarget<'_>,
+        sql_script: &SqlScript,
+    ) -> Result<StatementOutcomes<T>, Error>
+    where
+        T: FromRow + Send + 'static,
+    {
+        if self.query_runner.dry_run.is_some() {
+            let statement_errors = sql_script
+                .statements
+                .iter()
+                .filter_map(|sql_statement| {
+                    QueryRunner::dry_run_check(&sql_statement.sql_text)
+                        .err()
+                        .map(|error| (sql_statement.line, error))
+                })
+                .collect::<Vec<_>>();
+            if !statement_errors.is_empty() {
+                return Ok((Vec::new(), statement_errors));
+            }
+        }
+
+        let mut conn = self.query_runner.conn_acquire(pool).await?;
+        let connection_id = conn.id();
+        let conn_opts = conn.opts().clone();
+        let _query_registration = self.query_runner.query_register(query_target, &conn)?;
+        match self.query_runner.dry_run {
+            Some(_) => {
+                let mut transaction = conn
+                    .start_transaction(TxOpts::default())
+                    .await
+                    .map_err(Error::TransactionBegin)?;
+                let statement_outcomes = self
+                    .statements_exec(&mut transaction, &conn_opts, connection_id, sql_script)
+                    .await;
+                timeout(
+                    self.query_runner.timeouts.query,
+                    transaction.rollback().map_err(Error::TransactionRollback),
+                    |duration| Error::QueryTimeout { duration },
+                )
+                .await?;
+
+                Ok(statement_outcomes)
+            }
+            None => Ok(self
+                .statements_exec(&mut conn, &conn_opts, connection_id, sql_script)
+                .await),
+        }
+    }
+
+    /// Runs each statement in order on the connection.
+    ///
+    /// `conn_opts` and `connection_id` identify the connection, so that a
+    /// statement that times out can be killed. Once cancelled, the remaining
+    /// statements are not run.
+    async fn statements_exec<T, Q>(
+        &self,
+        conn: &mut Q,
+        conn_opts: &Opts,
+        connection_id: u32,
+        sql_script: &SqlScript,
+    ) -> StatementOutcomes<T>
+    where
+        T: FromRow + Send + 'static,
+        Q: Queryable,
+    {
+        let mut fetch_budget = FetchBudget::new(self.query_runner.result_limits);
+        let mut statement_results = Vec::with_capacity(sql_script.statements.len());
+        let mut statement_errors = Vec::new();
+        for SqlStatement { line, sql_text } in sql_script.statements.iter() {
+            if self.query_runner.cancelled() {
+                statement_errors.push((*line, Error::QueryCancelled));
+                break;
+            }
+
+            let result_sets = self
+                .query_runner
+                .query_timeout_kill(
+                    conn_opts,
+                    connection_id,
+                    QueryRunner::query_exec::<T, _>(
+                        conn,
+                        sql_text,
+                        QueryParams::Empty,
+                        self.query_runner.query_protocol,
+                        &mut fetch_budget,
+                    ),
+                )
+                .await;
+
+            match result_sets {
+                Ok(result_sets) => statement_results.push(StatementResult {
+                    line: *line,
+                    result_sets,
+                }),
+                Err(error) => {
+                    statement_errors.push((*line, error));
+                    if self.script_error_policy == ScriptErrorPolicy::StopOnError {
+                        break;
+                    }
+                }
+            }
+        }
+
+        (statement_results, statement_errors)
+    }
+}
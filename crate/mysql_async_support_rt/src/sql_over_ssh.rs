@@ -1,7 +1,8 @@
-use std::net::SocketAddr;
+use std::convert::TryFrom;
 
-use mysql_async::{OptsBuilder, PoolConstraints, PoolOpts};
-use mysql_async_support_model::{DbSchemaCred, Error};
+use mysql_async::{OptsBuilder, PoolConstraints, PoolOpts, SslOpts};
+use mysql_async_support_model::{DbConnOpts, DbSchemaCred, Error, SslMode};
+use ssh_jumper::model::HostSocketParams;
 
 use crate::FnWithPool;
 
@@ -15,8 +16,9 @@ impl SqlOverSsh {
     ///
     /// # Parameters
     ///
-    /// * `db_address`: Address to connect to the database server.
+    /// * `db_socket`: Address and port to connect to the database server.
     /// * `db_schema_cred`: Credentials to access a database schema.
+    /// * `db_conn_opts`: Options for connections to the database server.
     /// * `queries`: Async function that runs queries against the database.
     ///
     /// # Note
@@ -26,14 +28,17 @@ impl SqlOverSsh {
     /// concurrently.
     pub async fn exec<'f, Queries>(
         &'f self,
-        db_address: SocketAddr,
+        db_socket: HostSocketParams<'_>,
         db_schema_cred: DbSchemaCred<'f>,
+        db_conn_opts: &DbConnOpts<'_>,
         queries: Queries,
-    ) -> Result<<Queries as FnWithPool<'_>>::Output, <Queries as FnWithPool<'_>>::Error>
+    ) -> Result<<Queries as FnWithPool<'f>>::Output, <Queries as FnWithPool<'f>>::Error>
     where
         Queries: FnWithPool<'f>,
     {
-        let pool = self.db_pool_initialize(db_address, db_schema_cred).await?;
+        let pool = self
+            .db_pool_initialize(db_socket, db_schema_cred, db_conn_opts)
+            .await?;
 
         // Ideally we should be able to pass in `&mysql_async::Pool`, but from consumer
         // code, Rust cannot consolidate the lifetime references. See:
@@ -53,21 +58,119 @@ impl SqlOverSsh {
         Ok(data)
     }
 
-    async fn db_pool_initialize<'f>(
+    pub(crate) async fn db_pool_initialize<'f>(
         &self,
-        db_address: SocketAddr,
+        db_socket: HostSocketParams<'_>,
         db_schema_cred: DbSchemaCred<'f>,
+        db_conn_opts: &DbConnOpts<'_>,
     ) -> Result<mysql_async::Pool, Error> {
-        let db_opts = OptsBuilder::default()
-            .ip_or_hostname(db_address.ip().to_string())
-            .tcp_port(db_address.port())
-            .db_name(db_schema_cred.schema_name.as_deref())
-            .user(Some(db_schema_cred.username.as_ref()))
-            .pass(Some(db_schema_cred.password.as_ref()))
-            .pool_opts(Some(PoolOpts::default().with_constraints(
-                PoolConstraints::new(1, 2).ok_or(Error::PoolConstraintsInitialize)?,
-            )));
+        let db_opts =
+            OptsBuilder::default()
+                .ip_or_hostname(db_socket.address.to_string())
+                .tcp_port(db_socket.port)
+                .db_name(db_schema_cred.schema_name.as_deref())
+                .user(Some(db_schema_cred.username.as_ref()))
+                .pass(Some(db_schema_cred.password.as_ref()))
+                .pool_opts(Some(
+                    PoolOpts::default().with_constraints(
+                        PoolConstraints::new(db_conn_opts.pool_min, db_conn_opts.pool_max)
+                            .ok_or(Error::PoolConstraintsInitialize)?,
+                    ),
+                ))
+                .ssl_opts(Self::ssl_opts(db_conn_opts))
+                .compression(db_conn_opts.compression)
+                .tcp_keepalive(db_conn_opts.tcp_keepalive.map(|tcp_keepalive| {
+                    u32::try_from(tcp_keepalive.as_millis()).unwrap_or(u32::MAX)
+                }));
 
         Ok(mysql_async::Pool::new(db_opts))
     }
+
+    /// Returns the TLS options for the SSL mode, `None` if TLS is disabled.
+    fn ssl_opts(db_conn_opts: &DbConnOpts<'_>) -> Option<SslOpts> {
+        let (accept_invalid_certs, skip_domain_validation) = match db_conn_opts.ssl_mode {
+            SslMode::Disabled => return None,
+            SslMode::Required => (true, true),
+            SslMode::VerifyCa => (false, true),
+            SslMode::VerifyIdentity => (false, false),
+        };
+
+        let ssl_opts = SslOpts::default()
+            .with_root_cert_path(
+                db_conn_opts
+                    .ssl_ca_path
+                    .as_ref()
+                    .map(|ssl_ca_path| ssl_ca_path.to_path_buf()),
+            )
+            .with_pkcs12_path(
+                db_conn_opts
+                    .ssl_client_pkcs12_path
+                    .as_ref()
+                    .map(|pkcs12_path| pkcs12_path.to_path_buf()),
+            )
+            .with_password(
+                db_conn_opts
+                    .ssl_client_pkcs12_password
+                    .as_ref()
+                    .map(|password| password.to_string()),
+            )
+            .with_danger_accept_invalid_certs(accept_invalid_certs)
+            .with_danger_skip_domain_validation(skip_domain_validation);
+
+        Some(ssl_opts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, path::Path};
+
+    use mysql_async_support_model::{DbConnOpts, SslMode};
+
+    use super::SqlOverSsh;
+
+    #[test]
+    fn ssl_opts_disabled_is_none() {
+        let db_conn_opts = DbConnOpts::new().with_ssl_mode(SslMode::Disabled);
+
+        assert!(SqlOverSsh::ssl_opts(&db_conn_opts).is_none());
+    }
+
+    #[test]
+    fn ssl_opts_required_does_not_verify() {
+        let db_conn_opts = DbConnOpts::new().with_ssl_mode(SslMode::Required);
+        let ssl_opts = SqlOverSsh::ssl_opts(&db_conn_opts).expect("Expected TLS to be enabled.");
+
+        assert!(ssl_opts.accept_invalid_certs());
+        assert!(ssl_opts.skip_domain_validation());
+    }
+
+    #[test]
+    fn ssl_opts_verify_ca_verifies_certificate_only() {
+        let db_conn_opts = DbConnOpts::new()
+            .with_ssl_mode(SslMode::VerifyCa)
+            .with_ssl_ca_path(Cow::Borrowed(Path::new("ca.pem")));
+        let ssl_opts = SqlOverSsh::ssl_opts(&db_conn_opts).expect("Expected TLS to be enabled.");
+
+        assert!(!ssl_opts.accept_invalid_certs());
+        assert!(ssl_opts.skip_domain_validation());
+        assert_eq!(Some(Path::new("ca.pem")), ssl_opts.root_cert_path());
+    }
+
+    #[test]
+    fn ssl_opts_verify_identity_verifies_certificate_and_host() {
+        let db_conn_opts = DbConnOpts::new()
+            .with_ssl_mode(SslMode::VerifyIdentity)
+            .with_ssl_client_pkcs12(
+                Cow::Borrowed(Path::new("client.p12")),
+                Some(Cow::Borrowed("secret")),
+            );
+        let ssl_opts = SqlOverSsh::ssl_opts(&db_conn_opts).expect("Expected TLS to be enabled.");
+
+        assert!(!ssl_opts.accept_invalid_certs());
+        assert!(!ssl_opts.skip_domain_validation());
+        assert_eq!(None, ssl_opts.root_cert_path());
+        assert_eq!(Some(Path::new("client.p12")), ssl_opts.pkcs12_path());
+        assert_eq!(Some("secret"), ssl_opts.password());
+    }
 }
@@ -1,14 +1,22 @@
 use std::{
     collections::HashMap,
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
 };
 
-use futures::{stream, StreamExt, TryStreamExt};
-use mysql_async_support_model::{Error, QueryTarget, SshTunnelMap};
-use ssh_jumper::{
-    model::{HostAddress, HostSocketParams, JumpHostAuthParams},
-    SshJumper,
+use async_io::Async;
+use async_ssh2_lite::{AsyncChannel, AsyncSession, SessionConfiguration};
+use futures::{stream, AsyncReadExt, AsyncWriteExt, FutureExt, StreamExt, TryStreamExt};
+use mysql_async_support_model::{
+    Error, JumpHost, QueryTarget, SshSessionChain, SshTunnelListener, SshTunnelMap, Timeouts,
 };
+use plain_path::PlainPathExt;
+use ssh_jumper::model::{
+    Error as SshJumperError, HostAddress, HostSocketParams, JumpHostAuthParams,
+};
+
+use crate::ReconnectingSshSession;
 
 /// Opens SSH sessions and creates tunnels for query targets.
 #[derive(Debug)]
@@ -19,44 +27,48 @@ impl SshTunnelManager {
     /// port.
     const LOCAL_OS_CHOSEN_PORT: u16 = 0;
 
-    /// Opens an SSH session and creates a tunnel per query target.
+    /// Opens SSH sessions through the jump hosts and creates a tunnel per query
+    /// target.
     ///
-    /// The [`SshSession`] returned by this function should be kept alive until
-    /// all the tunnels are no longer needed.
+    /// The [`SshTunnelMap`] returned by this function should be kept alive
+    /// until all the tunnels are no longer needed. If the SSH sessions are lost
+    /// while the tunnels are in use, they are reopened up to
+    /// `ssh_reconnect_attempts` times.
     ///
     /// Callers of this function should appropriately limit the number of query
     /// targets per tunnel, perhaps by calling [`chunks`].
     ///
     /// [`chunks`]: std::slice::chunks
     pub async fn prepare_tunnels<'qt>(
-        jump_host_addr: &HostAddress<'_>,
-        jump_host_auth_params: &JumpHostAuthParams<'_>,
-        query_targets: &'qt [QueryTarget<'qt>],
+        jump_hosts: &[JumpHost<'_>],
+        query_targets: &[&'qt QueryTarget<'qt>],
+        ssh_reconnect_attempts: u32,
+        timeouts: Timeouts,
     ) -> Result<SshTunnelMap<'qt>, Error> {
+        let jump_host_address = jump_hosts
+            .last()
+            .map(|jump_host| jump_host.address.into_static())
+            .ok_or(Error::SshJumpHostsEmpty)?;
+        let jump_hosts = jump_hosts
+            .iter()
+            .cloned()
+            .map(JumpHost::into_static)
+            .collect::<Vec<_>>();
         let ssh_session =
-            SshJumper::open_ssh_session(jump_host_addr, jump_host_auth_params).await?;
-        let ssh_session_ref = &ssh_session;
+            ReconnectingSshSession::open(jump_hosts, ssh_reconnect_attempts, timeouts).await?;
+        let ssh_session = &Arc::new(ssh_session);
 
-        let qt_name_to_tunnel = stream::iter(query_targets)
+        let qt_name_to_tunnel = stream::iter(query_targets.iter().copied())
             .map(Result::<_, Error>::Ok)
             .try_fold(
                 HashMap::with_capacity(query_targets.len()),
                 |mut qt_name_to_tunnel, query_target| async move {
-                    let local_socket = SocketAddr::new(
-                        IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-                        Self::LOCAL_OS_CHOSEN_PORT,
-                    );
-
                     let target_socket = HostSocketParams {
-                        address: query_target.db_address.clone(),
-                        port: 3306,
+                        address: query_target.db_address.into_static(),
+                        port: query_target.db_port,
                     };
-                    let ssh_tunnel = SshJumper::open_direct_channel(
-                        ssh_session_ref,
-                        local_socket,
-                        &target_socket,
-                    )
-                    .await?;
+                    let ssh_tunnel =
+                        Self::open_tunnel_listener(Arc::clone(ssh_session), target_socket).await?;
 
                     qt_name_to_tunnel.insert(query_target.name.as_ref(), ssh_tunnel);
 
@@ -66,10 +78,285 @@ impl SshTunnelManager {
             .await?;
 
         let ssh_tunnel_map = SshTunnelMap {
-            ssh_session,
+            jump_host_address,
             qt_name_to_tunnel,
         };
 
         Ok(ssh_tunnel_map)
     }
+
+    /// Opens an SSH session to each jump host, each through the previous one.
+    ///
+    /// # Parameters
+    ///
+    /// * `jump_hosts`: Jump hosts to connect to, in order.
+    pub async fn open_ssh_session_chain(
+        jump_hosts: &[JumpHost<'_>],
+    ) -> Result<SshSessionChain, Error> {
+        let (jump_host_first, jump_hosts_nested) =
+            jump_hosts.split_first().ok_or(Error::SshJumpHostsEmpty)?;
+
+        let ssh_session = Self::open_ssh_session(jump_host_first)
+            .await
+            .map_err(|error| Self::hop_error(0, jump_host_first, error))?;
+        let mut ssh_session_chain = SshSessionChain {
+            ssh_sessions_nested: Vec::with_capacity(jump_hosts_nested.len()),
+            ssh_session,
+        };
+
+        for (index, jump_host) in jump_hosts_nested.iter().enumerate() {
+            let ssh_session_nested =
+                Self::open_ssh_session_nested(ssh_session_chain.last(), jump_host)
+                    .await
+                    .map_err(|error| Self::hop_error(index + 1, jump_host, error))?;
+            ssh_session_chain
+                .ssh_sessions_nested
+                .push(ssh_session_nested);
+        }
+
+        Ok(ssh_session_chain)
+    }
+
+    /// Returns the local address to a new tunnel to the given target host.
+    ///
+    /// Unlike [`SshJumper::open_direct_channel`], this accepts any SSH session,
+    /// including sessions opened through another jump host.
+    ///
+    /// # Parameters
+    ///
+    /// * `ssh_session`: Existing SSH session to create the tunnel through.
+    /// * `target_socket`: The address of the target host to connect to.
+    ///
+    /// [`SshJumper::open_direct_channel`]: ssh_jumper::SshJumper::open_direct_channel
+    pub async fn open_direct_channel(
+        ssh_session: &AsyncSession<TcpStream>,
+        target_socket: &HostSocketParams<'_>,
+    ) -> Result<SocketAddr, SshJumperError> {
+        let target_host_address = target_socket.address.to_string();
+        let async_channel = ssh_session
+            .channel_direct_tcpip(&target_host_address, target_socket.port, None)
+            .await
+            .map_err(SshJumperError::SshTunnelOpenFail)?;
+
+        Self::spawn_channel_streamer(async_channel).await
+    }
+
+    /// Opens an SSH session to the first jump host, on the jump host's port.
+    async fn open_ssh_session(
+        jump_host: &JumpHost<'_>,
+    ) -> Result<AsyncSession<TcpStream>, SshJumperError> {
+        let jump_host_socket_addr = match &jump_host.address {
+            HostAddress::IpAddr(ip_addr) => SocketAddr::from((*ip_addr, jump_host.port)),
+            HostAddress::HostName(host_name) => {
+                tokio::net::lookup_host((host_name.as_ref(), jump_host.port))
+                    .await
+                    .map_err(|io_error| SshJumperError::JumpHostConnectFail {
+                        jump_host_addr: jump_host.address.into_static(),
+                        io_error,
+                    })?
+                    .next()
+                    .ok_or_else(|| SshJumperError::JumpHostIpResolutionFail {
+                        jump_host_addr: host_name.to_string(),
+                    })?
+            }
+        };
+
+        Self::ssh_session_establish(jump_host, jump_host_socket_addr).await
+    }
+
+    /// Opens an SSH session to a jump host through an existing SSH session.
+    async fn open_ssh_session_nested(
+        ssh_session: &AsyncSession<TcpStream>,
+        jump_host: &JumpHost<'_>,
+    ) -> Result<AsyncSession<TcpStream>, SshJumperError> {
+        let jump_host_socket = HostSocketParams {
+            address: jump_host.address.clone(),
+            port: jump_host.port,
+        };
+        let jump_host_tunnel = Self::open_direct_channel(ssh_session, &jump_host_socket).await?;
+
+        Self::ssh_session_establish(jump_host, jump_host_tunnel).await
+    }
+
+    /// Connects to the jump host's SSH server at the socket address, and
+    /// authenticates with it.
+    async fn ssh_session_establish(
+        jump_host: &JumpHost<'_>,
+        socket_addr: SocketAddr,
+    ) -> Result<AsyncSession<TcpStream>, SshJumperError> {
+        let stream = Async::<TcpStream>::connect(socket_addr)
+            .await
+            .map_err(|io_error| SshJumperError::JumpHostConnectFail {
+                jump_host_addr: jump_host.address.into_static(),
+                io_error,
+            })?;
+
+        let mut session_configuration = SessionConfiguration::new();
+        session_configuration.set_compress(true);
+        let mut session = AsyncSession::new(stream, Some(session_configuration))
+            .map_err(SshJumperError::AsyncSessionInitialize)?;
+
+        session
+            .handshake()
+            .await
+            .map_err(SshJumperError::SshHandshakeFail)?;
+        Self::userauth(&session, &jump_host.auth_params).await?;
+
+        Ok(session)
+    }
+
+    async fn userauth(
+        session: &AsyncSession<TcpStream>,
+        jump_host_auth_params: &JumpHostAuthParams<'_>,
+    ) -> Result<(), SshJumperError> {
+        let private_key = jump_host_auth_params
+            .private_key
+            .plain()
+            .map_err(SshJumperError::PrivateKeyPlainPath)?;
+
+        session
+            .userauth_pubkey_file(
+                &jump_host_auth_params.user_name,
+                None,
+                &private_key,
+                jump_host_auth_params.passphrase.as_deref(),
+            )
+            .await
+            .map_err(SshJumperError::SshUserAuthFail)?;
+
+        if session.authenticated() {
+            Ok(())
+        } else {
+            Err(session
+                .last_error()
+                .map(SshJumperError::SshUserAuthError)
+                .unwrap_or(SshJumperError::SshUserAuthUnknownError))
+        }
+    }
+
+    /// Opens a tunnel to the target host that accepts any number of local
+    /// connections.
+    ///
+    /// Unlike [`Self::open_direct_channel`], a new direct channel is opened
+    /// through the SSH session for each accepted connection, so the returned
+    /// tunnel can be used by a connection pool for as long as it is kept alive.
+    ///
+    /// If the SSH session has been lost, it is reopened before the channel is
+    /// opened. When the channel cannot be opened, the local connection is
+    /// closed, and the error is recorded in the [`SshTunnelListener`].
+    ///
+    /// # Parameters
+    ///
+    /// * `ssh_session`: SSH session to create tunnels through.
+    /// * `target_socket`: The address of the target host to connect to.
+    pub async fn open_tunnel_listener(
+        ssh_session: Arc<ReconnectingSshSession>,
+        target_socket: HostSocketParams<'static>,
+    ) -> Result<SshTunnelListener, SshJumperError> {
+        let (local_socket_addr, local_socket_listener) = Self::local_listener_bind().await?;
+
+        let error = Arc::new(Mutex::new(None));
+        let join_handle = {
+            let error = Arc::clone(&error);
+            tokio::task::spawn(async move {
+                while let Ok((local_stream, _)) = local_socket_listener.accept().await {
+                    match ssh_session.channel_direct_tcpip(&target_socket).await {
+                        Ok(channel) => {
+                            let _detached_task =
+                                tokio::task::spawn(Self::channel_forward(local_stream, channel));
+                        }
+                        Err(channel_error) => {
+                            // The local stream is dropped, and the client sees the connection
+                            // as closed.
+                            *error
+                                .lock()
+                                .expect("`SshTunnelListener` error lock poisoned.") =
+                                Some(channel_error);
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(SshTunnelListener::new(
+            local_socket_addr,
+            join_handle,
+            error,
+        ))
+    }
+
+    /// Forwards traffic between a local socket and the SSH channel.
+    ///
+    /// Like `ssh_jumper`, the listener accepts a single connection, and the
+    /// streamer task ends when either side closes the connection.
+    async fn spawn_channel_streamer(
+        channel: AsyncChannel<TcpStream>,
+    ) -> Result<SocketAddr, SshJumperError> {
+        let (local_socket_addr, local_socket_listener) = Self::local_listener_bind().await?;
+
+        let _detached_task = tokio::task::spawn(async move {
+            let (local_stream, _) = local_socket_listener.accept().await?;
+            Self::channel_forward(local_stream, channel).await
+        });
+
+        Ok(local_socket_addr)
+    }
+
+    /// Binds a listener to an OS chosen port on the local host.
+    async fn local_listener_bind() -> Result<(SocketAddr, Async<TcpListener>), SshJumperError> {
+        let local_socket = SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            Self::LOCAL_OS_CHOSEN_PORT,
+        );
+        let local_listener = TcpListener::bind(local_socket).map_err(|io_error| {
+            SshJumperError::LocalSocketBind {
+                local_socket,
+                io_error,
+            }
+        })?;
+        let local_socket_addr =
+            local_listener
+                .local_addr()
+                .map_err(|io_error| SshJumperError::LocalSocketAddr {
+                    local_socket,
+                    io_error,
+                })?;
+        let local_socket_listener =
+            Async::new(local_listener).map_err(SshJumperError::SshTunnelListenerCreate)?;
+
+        Ok((local_socket_addr, local_socket_listener))
+    }
+
+    /// Copies traffic between the local stream and the SSH channel until either
+    /// side closes the connection.
+    async fn channel_forward(
+        mut local_stream: Async<TcpStream>,
+        mut channel: AsyncChannel<TcpStream>,
+    ) -> Result<(), io::Error> {
+        let mut buf_channel = vec![0; 2048];
+        let mut buf_local_stream = vec![0; 2048];
+
+        loop {
+            futures::select! {
+                read_result = local_stream.read(&mut buf_local_stream).fuse() => match read_result? {
+                    0 => break,
+                    n => channel.write_all(&buf_local_stream[..n]).await?,
+                },
+                read_result = channel.read(&mut buf_channel).fuse() => match read_result? {
+                    0 => break,
+                    n => local_stream.write_all(&buf_channel[..n]).await?,
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hop_error(index: usize, jump_host: &JumpHost<'_>, error: SshJumperError) -> Error {
+        Error::SshJumpHostConnect {
+            hop_number: index + 1,
+            jump_host_address: jump_host.address.into_static(),
+            error: Box::new(error),
+        }
+    }
 }
@@ -0,0 +1,21 @@
+use std::{
+    borrow::Cow,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use mysql_async_support_model::{DbSchemaCred, QueryTarget};
+use ssh_jumper::model::HostAddress;
+
+/// Returns a query target with the given name, for tests that do not connect
+/// to it.
+pub(crate) fn query_target(name: &'static str) -> QueryTarget<'static> {
+    QueryTarget::new(
+        Cow::Borrowed(name),
+        HostAddress::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        DbSchemaCred {
+            schema_name: None,
+            username: Cow::Borrowed("user"),
+            password: Cow::Borrowed("pass"),
+        },
+    )
+}
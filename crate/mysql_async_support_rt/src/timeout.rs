@@ -0,0 +1,208 @@
+use std::{future::Future, time::Duration};
+
+use mysql_async_support_model::Error;
+use tokio::time::Instant;
+
+/// Runs the future, returning the timeout error if it does not complete
+/// within `duration`.
+///
+/// If `duration` is `None`, the future is not time-bounded.
+pub(crate) async fn timeout<Fut, T, E>(
+    duration: Option<Duration>,
+    future: Fut,
+    timeout_error: impl FnOnce(Duration) -> E,
+) -> Result<T, E>
+where
+    Fut: Future<Output = Result<T, E>>,
+{
+    match duration {
+        Some(duration) => tokio::time::timeout(duration, future)
+            .await
+            .unwrap_or_else(|_elapsed| Err(timeout_error(duration))),
+        None => future.await,
+    }
+}
+
+/// Point in time by which all phases of a query target must complete.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TargetDeadline {
+    /// When the deadline elapses.
+    instant: Instant,
+    /// Duration from when the deadline was started.
+    duration: Duration,
+}
+
+impl TargetDeadline {
+    /// Returns a deadline `duration` from now, `None` if there is no deadline.
+    pub(crate) fn start(duration: Option<Duration>) -> Option<Self> {
+        duration.map(|duration| Self {
+            instant: Instant::now() + duration,
+            duration,
+        })
+    }
+
+    /// Returns a deadline `duration` from when `elapsed` of it was already
+    /// spent, `None` if there is no deadline.
+    ///
+    /// This resumes a deadline whose phases are not run back to back, so time
+    /// spent waiting between phases is not counted.
+    pub(crate) fn resume(duration: Option<Duration>, elapsed: Duration) -> Option<Self> {
+        duration.map(|duration| Self {
+            instant: Instant::now() + duration.saturating_sub(elapsed),
+            duration,
+        })
+    }
+
+    /// Returns how much of the deadline's duration has passed.
+    pub(crate) fn elapsed(self) -> Duration {
+        self.duration
+            .saturating_sub(self.instant.saturating_duration_since(Instant::now()))
+    }
+
+    /// Runs the future, returning [`Error::TargetTimeout`] if it does not
+    /// complete before the deadline.
+    ///
+    /// If `target_deadline` is `None`, the future is not time-bounded.
+    pub(crate) async fn run<Fut, T, E>(target_deadline: Option<Self>, future: Fut) -> Result<T, E>
+    where
+        Fut: Future<Output = Result<T, E>>,
+        E: From<Error>,
+    {
+        match target_deadline {
+            Some(TargetDeadline { instant, duration }) => tokio::time::timeout_at(instant, future)
+                .await
+                .unwrap_or_else(|_elapsed| Err(E::from(Error::TargetTimeout { duration }))),
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, time::Duration};
+
+    use mysql_async_support_model::Error;
+
+    use super::{timeout, TargetDeadline};
+
+    /// Runs the future on a runtime whose clock only advances when every task
+    /// is waiting on a timer.
+    fn block_on_paused<Fut>(future: Fut) -> Fut::Output
+    where
+        Fut: Future,
+    {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .expect("Failed to build runtime.")
+            .block_on(future)
+    }
+
+    async fn sleep_ok(duration: Duration) -> Result<(), Error> {
+        tokio::time::sleep(duration).await;
+        Ok(())
+    }
+
+    #[test]
+    fn timeout_completes_within_duration() {
+        let result = block_on_paused(timeout(
+            Some(Duration::from_secs(10)),
+            sleep_ok(Duration::from_secs(5)),
+            |duration| Error::QueryTimeout { duration },
+        ));
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn timeout_returns_phase_error_when_elapsed() {
+        let result = block_on_paused(timeout(
+            Some(Duration::from_secs(5)),
+            sleep_ok(Duration::from_secs(10)),
+            |duration| Error::QueryTimeout { duration },
+        ));
+
+        assert!(matches!(
+            result,
+            Err(Error::QueryTimeout { duration }) if duration == Duration::from_secs(5)
+        ));
+    }
+
+    #[test]
+    fn timeout_none_is_not_bounded() {
+        let result = block_on_paused(timeout(
+            None,
+            sleep_ok(Duration::from_secs(3600)),
+            |duration| Error::QueryTimeout { duration },
+        ));
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn target_deadline_spans_phases() {
+        let result = block_on_paused(async {
+            let target_deadline = TargetDeadline::start(Some(Duration::from_secs(10)));
+            TargetDeadline::run(target_deadline, sleep_ok(Duration::from_secs(6))).await?;
+
+            // Each phase is within the duration, but together they are not.
+            TargetDeadline::run(target_deadline, sleep_ok(Duration::from_secs(6))).await
+        });
+
+        assert!(matches!(
+            result,
+            Err(Error::TargetTimeout { duration }) if duration == Duration::from_secs(10)
+        ));
+    }
+
+    #[test]
+    fn target_deadline_elapses_before_phase_timeout() {
+        let result = block_on_paused(async {
+            let target_deadline = TargetDeadline::start(Some(Duration::from_secs(5)));
+            TargetDeadline::run(
+                target_deadline,
+                timeout(
+                    Some(Duration::from_secs(10)),
+                    sleep_ok(Duration::from_secs(20)),
+                    |duration| Error::QueryTimeout { duration },
+                ),
+            )
+            .await
+        });
+
+        assert!(matches!(result, Err(Error::TargetTimeout { .. })));
+    }
+
+    #[test]
+    fn target_deadline_resume_counts_elapsed_phases() {
+        let result = block_on_paused(async {
+            let target_deadline = TargetDeadline::start(Some(Duration::from_secs(10)));
+            TargetDeadline::run(target_deadline, sleep_ok(Duration::from_secs(6))).await?;
+            let elapsed = target_deadline
+                .map(TargetDeadline::elapsed)
+                .unwrap_or_default();
+
+            // Time spent between phases is not counted.
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            let target_deadline = TargetDeadline::resume(Some(Duration::from_secs(10)), elapsed);
+            TargetDeadline::run(target_deadline, sleep_ok(Duration::from_secs(3))).await?;
+            TargetDeadline::run(target_deadline, sleep_ok(Duration::from_secs(3))).await
+        });
+
+        assert!(matches!(
+            result,
+            Err(Error::TargetTimeout { duration }) if duration == Duration::from_secs(10)
+        ));
+    }
+
+    #[test]
+    fn target_deadline_none_is_not_bounded() {
+        let result = block_on_paused(TargetDeadline::run(
+            TargetDeadline::start(None),
+            sleep_ok(Duration::from_secs(3600)),
+        ));
+
+        assert!(result.is_ok());
+    }
+}
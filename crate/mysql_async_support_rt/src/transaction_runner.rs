@@ -0,0 +1,508 @@
+use std::{
+    future::Future,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{
+    future,
+    stream::{self, StreamExt},
+    FutureExt,
+};
+use mysql_async::{prelude::Queryable, Conn, Pool};
+use mysql_async_support_model::{
+    ConnectionRoute, Error, QueryTarget, RouteMap, TransactionMode, TransactionOutcome,
+    TransactionResult,
+};
+
+use crate::{
+    timeout::{timeout, TargetDeadline},
+    ConnectionRouteManager, FnWithConn, QueryRunner,
+};
+
+/// Runs queries on multiple query targets in transactions that are only
+/// committed if every query target succeeds.
+#[derive(Clone)]
+pub struct TransactionRunner {
+    /// Connection settings and limits used to run the queries.
+    pub query_runner: QueryRunner,
+    /// Whether to use local or XA transactions.
+    pub transaction_mode: TransactionMode,
+}
+
+/// Transaction on a query target that is not yet committed or rolled back.
+struct TransactionPending<'f, T, E> {
+    /// The query target the transaction is on.
+    query_target: &'f QueryTarget<'f>,
+    /// XA transaction ID, unused for local transactions.
+    xid: String,
+    /// Pool the connection was retrieved from.
+    pool: Option<Pool>,
+    /// Connection holding the open transaction.
+    ///
+    /// `None` if the transaction was never started, or the connection was
+    /// dropped, in which case the server rolls back the transaction when the
+    /// pool is disconnected.
+    conn: Option<Conn>,
+    /// Result of the queries.
+    result: Result<T, E>,
+}
+
+impl TransactionRunner {
+    /// Returns a new `TransactionRunner`.
+    pub fn new(query_runner: QueryRunner, transaction_mode: TransactionMode) -> Self {
+        Self {
+            query_runner,
+            transaction_mode,
+        }
+    }
+
+    /// Runs queries on multiple query targets, each in its own transaction.
+    ///
+    /// Every query target is connected to and runs `queries` in a transaction,
+    /// which is prepared but left open. Only when all query targets have
+    /// succeeded are the transactions committed, otherwise every transaction
+    /// is rolled back. Query targets are grouped and chunked in the same way as
+    /// [`QueryRunner::exec_multi`], but tunnels are kept open until every
+    /// transaction is finished.
+    ///
+    /// `queries` must not commit or roll back the transaction, and with
+    /// [`TransactionMode::Xa`], must not run statements that implicitly commit,
+    /// such as DDL.
+    ///
+    /// The [`target`] timeout applies until the transaction is prepared, and
+    /// the result for each query target is returned in the order the query
+    /// targets were grouped. `queries` is bounded by the [`query`] timeout, and
+    /// is killed on the server if it times out or is cancelled through the
+    /// [`cancel_handle`].
+    ///
+    /// Committing and rolling back each transaction are also bounded by the
+    /// [`query`] timeout. If an XA commit fails or times out, the transaction
+    /// may be left prepared on the server, and its XA transaction ID is
+    /// returned in [`TransactionOutcome::CommitFailed`] so that it can be
+    /// resolved.
+    ///
+    /// When [`dry_run`] is set, every transaction is rolled back even if all
+    /// query targets succeed, and no before-image is selected.
+    ///
+    /// [`target`]: mysql_async_support_model::Timeouts::target
+    /// [`query`]: mysql_async_support_model::Timeouts::query
+    /// [`cancel_handle`]: QueryRunner::cancel_handle
+    /// [`dry_run`]: QueryRunner::dry_run
+    pub async fn exec_multi<'f, Queries>(
+        &'f self,
+        connection_route: &ConnectionRoute<'_>,
+        query_targets: &'f [QueryTarget<'f>],
+        queries: Queries,
+    ) -> Vec<
+        TransactionResult<
+            'f,
+            <Queries as FnWithConn<'f>>::Output,
+            <Queries as FnWithConn<'f>>::Error,
+        >,
+    >
+    where
+        Queries: FnWithConn<'f> + Copy,
+    {
+        let query_runner = &self.query_runner;
+        let gtrid = Self::gtrid_generate();
+        let gtrid = gtrid.as_str();
+        let route_groups = ConnectionRouteManager::group_by_route(connection_route, query_targets);
+        let (route_maps, transactions_pending) =
+            stream::iter(query_runner.route_chunks(&route_groups).enumerate())
+                .map(
+                    |(chunk_index, (connection_route, query_targets_chunk))| async move {
+                        let target_deadline = TargetDeadline::start(query_runner.timeouts.target);
+                        let route_map = TargetDeadline::run(
+                            target_deadline,
+                            query_runner.route_prepare(connection_route, query_targets_chunk),
+                        )
+                        .await;
+
+                        match route_map {
+                            Ok(route_map) => {
+                                let transactions_pending =
+                                    stream::iter(query_targets_chunk.iter().copied().enumerate())
+                                        .map(|(target_index, query_target)| {
+                                            let xid = Self::xid(gtrid, chunk_index, target_index);
+                                            self.transaction_run(
+                                                &route_map,
+                                                query_target,
+                                                queries,
+                                                xid,
+                                                target_deadline,
+                                            )
+                                        })
+                                        .buffered(query_runner.tunnels_per_ssh_connection)
+                                        .collect::<Vec<_>>()
+                                        .await;
+                                (Some(route_map), transactions_pending)
+                            }
+                            Err(e) => {
+                                let mut error = Some(e);
+                                let transactions_pending = query_targets_chunk
+                                    .iter()
+                                    .map(|query_target| {
+                                        let error = error.take().unwrap_or(Error::SshConnInit);
+                                        TransactionPending {
+                                            query_target,
+                                            xid: String::new(),
+                                            pool: None,
+                                            conn: None,
+                                            result: Err(<Queries as FnWithConn<'f>>::Error::from(
+                                                error,
+                                            )),
+                                        }
+                                    })
+                                    .collect::<Vec<_>>();
+                                (None, transactions_pending)
+                            }
+                        }
+                    },
+                )
+                .buffered(query_runner.ssh_concurrent_limit)
+                .fold(
+                    (Vec::new(), Vec::with_capacity(query_targets.len())),
+                    |(mut route_maps, mut transactions_pending),
+                     (route_map, transactions_pending_chunk)| async move {
+                        route_maps.extend(route_map);
+                        transactions_pending.extend(transactions_pending_chunk);
+
+                        (route_maps, transactions_pending)
+                    },
+                )
+                .await;
+
+        let commit = query_runner.dry_run.is_none()
+            && transactions_pending
+                .iter()
+                .all(|transaction_pending| transaction_pending.result.is_ok());
+        let transaction_results = future::join_all(
+            transactions_pending
+                .into_iter()
+                .map(|transaction_pending| self.transaction_finish(transaction_pending, commit)),
+        )
+        .await;
+
+        // Tunnels must stay open until every transaction is finished.
+        drop(route_maps);
+
+        transaction_results
+    }
+
+    /// Starts a transaction on the query target, runs the queries, and
+    /// prepares the transaction to be committed.
+    async fn transaction_run<'f, Queries>(
+        &'f self,
+        route_map: &RouteMap<'f>,
+        query_target: &'f QueryTarget<'f>,
+        queries: Queries,
+        xid: String,
+        target_deadline: Option<TargetDeadline>,
+    ) -> TransactionPending<
+        'f,
+        <Queries as FnWithConn<'f>>::Output,
+        <Queries as FnWithConn<'f>>::Error,
+    >
+    where
+        Queries: FnWithConn<'f>,
+    {
+        let transaction_mode = self.transaction_mode;
+        let mut pool = None;
+        let mut conn = None;
+        // Options and ID of the connection while `queries` runs, so the query can
+        // be killed if it does not finish.
+        let mut query_running = None;
+        let result = TargetDeadline::run(target_deadline, async {
+            let db_socket = route_map.db_socket(query_target)?;
+            let pool = pool.insert(
+                self.query_runner
+                    .sql_over_ssh
+                    .db_pool_initialize(
+                        db_socket,
+                        query_target.db_schema_cred.clone(),
+                        self.query_runner.db_conn_opts(query_target),
+                    )
+                    .await?,
+            );
+            let mut conn_new = self.query_runner.conn_acquire(pool).await?;
+            let _query_registration = self.query_runner.query_register(query_target, &conn_new)?;
+            Self::transaction_begin(&mut conn_new, transaction_mode, &xid).await?;
+
+            query_running = Some((conn_new.opts().clone(), conn_new.id()));
+            let (conn_new, result) = timeout(
+                self.query_runner.timeouts.query,
+                queries.call(conn_new).map(Ok),
+                |duration| Error::QueryTimeout { duration },
+            )
+            .await?;
+            query_running = None;
+            let conn = conn.insert(conn_new);
+            let output = result?;
+
+            Self::transaction_prepare(conn, transaction_mode, &xid).await?;
+            Ok::<_, <Queries as FnWithConn<'f>>::Error>(output)
+        })
+        .await
+        .map_err(|error| {
+            route_map
+                .tunnel_error_take(query_target)
+                .map(<Queries as FnWithConn<'f>>::Error::from)
+                .unwrap_or(error)
+        });
+
+        if let Some((conn_opts, connection_id)) = query_running {
+            // The query keeps running on the server unless it is killed. Failing to kill it
+            // does not change the outcome, as the transaction is rolled back regardless, so
+            // the error is discarded.
+            let _ = QueryRunner::query_kill(&conn_opts, connection_id, self.query_runner.timeouts)
+                .await;
+        }
+
+        TransactionPending {
+            query_target,
+            xid,
+            pool,
+            conn,
+            result,
+        }
+    }
+
+    /// Commits or rolls back the transaction, and disconnects its pool.
+    async fn transaction_finish<'f, T, E>(
+        &self,
+        transaction_pending: TransactionPending<'f, T, E>,
+        commit: bool,
+    ) -> TransactionResult<'f, T, E> {
+        let TransactionPending {
+            query_target,
+            xid,
+            pool,
+            conn,
+            result,
+        } = transaction_pending;
+
+        let transaction_mode = self.transaction_mode;
+        let query_timeout = self.query_runner.timeouts.query;
+        let outcome = match conn {
+            Some(mut conn) if commit => {
+                let transaction_commit =
+                    Self::transaction_commit(&mut conn, transaction_mode, &xid);
+                match Self::transaction_finish_timeout(query_timeout, transaction_commit).await {
+                    Ok(()) => TransactionOutcome::Committed,
+                    Err(error) => TransactionOutcome::CommitFailed {
+                        xid: Self::xid_reported(transaction_mode, xid),
+                        error,
+                    },
+                }
+            }
+            Some(mut conn) => {
+                let transaction_rollback =
+                    Self::transaction_rollback(&mut conn, transaction_mode, &xid);
+                match Self::transaction_finish_timeout(query_timeout, transaction_rollback).await {
+                    Ok(()) => TransactionOutcome::RolledBack,
+                    Err(error) => TransactionOutcome::RollbackFailed {
+                        xid: Self::xid_reported(transaction_mode, xid),
+                        error,
+                    },
+                }
+            }
+            None => TransactionOutcome::RolledBack,
+        };
+
+        if let Some(pool) = pool {
+            // The transaction is already finished, so failing to cleanly
+            // disconnect the pool is not actionable, and the error is discarded.
+            let _ = pool.disconnect().await;
+        }
+
+        TransactionResult {
+            query_target,
+            result,
+            outcome,
+        }
+    }
+
+    /// Bounds committing or rolling back the transaction by the query
+    /// timeout.
+    async fn transaction_finish_timeout<Fut>(
+        query_timeout: Option<Duration>,
+        transaction_finish: Fut,
+    ) -> Result<(), Error>
+    where
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        timeout(query_timeout, transaction_finish, |duration| {
+            Error::QueryTimeout { duration }
+        })
+        .await
+    }
+
+    async fn transaction_begin(
+        conn: &mut Conn,
+        transaction_mode: TransactionMode,
+        xid: &str,
+    ) -> Result<(), Error> {
+        let sql_text = match transaction_mode {
+            TransactionMode::Local => String::from("START TRANSACTION"),
+            TransactionMode::Xa => format!("XA START {}", xid),
+        };
+        conn.query_drop(sql_text)
+            .await
+            .map_err(Error::TransactionBegin)
+    }
+
+    async fn transaction_prepare(
+        conn: &mut Conn,
+        transaction_mode: TransactionMode,
+        xid: &str,
+    ) -> Result<(), Error> {
+        match transaction_mode {
+            // Local transactions have no prepared state, so they are left open.
+            TransactionMode::Local => Ok(()),
+            TransactionMode::Xa => {
+                conn.query_drop(format!("XA END {}", xid))
+                    .await
+                    .map_err(Error::TransactionPrepare)?;
+                conn.query_drop(format!("XA PREPARE {}", xid))
+                    .await
+                    .map_err(Error::TransactionPrepare)
+            }
+        }
+    }
+
+    async fn transaction_commit(
+        conn: &mut Conn,
+        transaction_mode: TransactionMode,
+        xid: &str,
+    ) -> Result<(), Error> {
+        let sql_text = match transaction_mode {
+            TransactionMode::Local => String::from("COMMIT"),
+            TransactionMode::Xa => format!("XA COMMIT {}", xid),
+        };
+        conn.query_drop(sql_text)
+            .await
+            .map_err(Error::TransactionCommit)
+    }
+
+    async fn transaction_rollback(
+        conn: &mut Conn,
+        transaction_mode: TransactionMode,
+        xid: &str,
+    ) -> Result<(), Error> {
+        let sql_text = match transaction_mode {
+            TransactionMode::Local => String::from("ROLLBACK"),
+            TransactionMode::Xa => {
+                // The transaction is still active if the queries failed before
+                // it was ended. If it was already ended, this fails and is
+                // ignored.
+                let _ = conn.query_drop(format!("XA END {}", xid)).await;
+                format!("XA ROLLBACK {}", xid)
+            }
+        };
+        conn.query_drop(sql_text)
+            .await
+            .map_err(Error::TransactionRollback)
+    }
+
+    /// Returns the XA transaction ID for a query target, in the form used by
+    /// `XA` statements.
+    ///
+    /// The branch qualifier is the query target's chunk and index within the
+    /// chunk, so it is unique within the run.
+    fn xid(gtrid: &str, chunk_index: usize, target_index: usize) -> String {
+        format!("'{}','{}.{}'", gtrid, chunk_index, target_index)
+    }
+
+    /// Returns the XA transaction ID to report in a failed outcome, or `None`
+    /// for local transactions, which have no ID.
+    fn xid_reported(transaction_mode: TransactionMode, xid: String) -> Option<String> {
+        match transaction_mode {
+            TransactionMode::Local => None,
+            TransactionMode::Xa => Some(xid),
+        }
+    }
+
+    /// Returns a global transaction ID that is unique to this run.
+    ///
+    /// Each query target's XA transaction ID combines this with a branch
+    /// qualifier for the query target.
+    fn gtrid_generate() -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        format!("mysql_async_support_{}_{}", std::process::id(), nanos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysql_async_support_model::{Error, TransactionMode, TransactionOutcome};
+
+    use super::{TransactionPending, TransactionRunner};
+    use crate::{test_support::query_target, QueryRunner};
+
+    /// Maximum length of an XA global transaction ID or branch qualifier.
+    const XID_PART_LEN_MAX: usize = 64;
+
+    fn transaction_runner(transaction_mode: TransactionMode) -> TransactionRunner {
+        TransactionRunner::new(
+            QueryRunner::new(
+                QueryRunner::SSH_CONCURRENT_LIMIT_DEFAULT,
+                QueryRunner::TUNNELS_PER_SSH_CONNECTION_DEFAULT,
+            ),
+            transaction_mode,
+        )
+    }
+
+    #[test]
+    fn gtrid_fits_xa_limit_and_is_quotable() {
+        let gtrid = TransactionRunner::gtrid_generate();
+
+        assert!(gtrid.len() <= XID_PART_LEN_MAX);
+        assert!(!gtrid.contains('\''));
+    }
+
+    #[test]
+    fn xid_quotes_gtrid_and_branch() {
+        assert_eq!(
+            "'gtrid','2.3'",
+            TransactionRunner::xid("gtrid", 2, 3).as_str()
+        );
+    }
+
+    #[test]
+    fn xid_reported_only_for_xa() {
+        assert_eq!(
+            Some(String::from("'gtrid','0.0'")),
+            TransactionRunner::xid_reported(TransactionMode::Xa, String::from("'gtrid','0.0'"))
+        );
+        assert_eq!(
+            None,
+            TransactionRunner::xid_reported(TransactionMode::Local, String::from("'gtrid','0.0'"))
+        );
+    }
+
+    #[test]
+    fn transaction_finish_without_conn_is_rolled_back() {
+        let transaction_runner = transaction_runner(TransactionMode::Xa);
+        let query_target = query_target("target");
+        let transaction_pending = TransactionPending::<'_, (), Error> {
+            query_target: &query_target,
+            xid: TransactionRunner::xid("gtrid", 0, 0),
+            pool: None,
+            conn: None,
+            result: Err(Error::SshConnInit),
+        };
+
+        let transaction_result = futures::executor::block_on(
+            transaction_runner.transaction_finish(transaction_pending, true),
+        );
+
+        assert!(matches!(
+            transaction_result.outcome,
+            TransactionOutcome::RolledBack
+        ));
+        assert!(matches!(transaction_result.result, Err(Error::SshConnInit)));
+    }
+}